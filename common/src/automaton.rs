@@ -0,0 +1,174 @@
+use crate::Grid;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighborhood {
+    // The four orthogonally adjacent cells
+    VonNeumann,
+    // The eight orthogonally and diagonally adjacent cells
+    Moore,
+}
+
+// The direction cells move in during a sweep.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Gravity {
+    Right,
+    Up,
+    Left,
+    Down,
+}
+
+pub struct Cell<'a, T> {
+    grid: &'a Grid<T>,
+    neighborhood: Neighborhood,
+    pub x: usize,
+    pub y: usize,
+}
+
+impl<'a, T> Cell<'a, T> {
+    pub fn value(&self) -> &'a T {
+        self.grid.get(self.x, self.y).unwrap()
+    }
+
+    pub fn neighbors(&self) -> impl Iterator<Item = &'a T> {
+        let grid = self.grid;
+        let neighborhood = self.neighborhood;
+        let (x, y) = (self.x, self.y);
+        grid.adjacent(x, y)
+            .filter(move |&(nx, ny)| match neighborhood {
+                Neighborhood::VonNeumann => (nx == x) != (ny == y),
+                Neighborhood::Moore => (nx, ny) != (x, y),
+            })
+            .map(move |(nx, ny)| grid.get(nx, ny).unwrap())
+    }
+
+    pub fn count<F: FnMut(&T) -> bool>(&self, mut predicate: F) -> usize {
+        self.neighbors().filter(|n| predicate(n)).count()
+    }
+}
+
+// Steps a grid of cells, double-buffering so that every cell is updated from
+// the same generation.
+#[derive(Clone)]
+pub struct Automaton<T> {
+    current: Grid<T>,
+    next: Grid<T>,
+}
+
+impl<T: Clone + PartialEq> Automaton<T> {
+    pub fn new(grid: Grid<T>) -> Self {
+        Self {
+            next: grid.clone(),
+            current: grid,
+        }
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.current
+    }
+
+    pub fn into_grid(self) -> Grid<T> {
+        self.current
+    }
+
+    // Replaces every cell with the result of `rule`, returning whether any
+    // cell changed.
+    pub fn step<F>(&mut self, neighborhood: Neighborhood, mut rule: F) -> bool
+    where
+        F: FnMut(Cell<'_, T>) -> T,
+    {
+        let mut changed = false;
+        for (x, y) in self.current.iter() {
+            let value = rule(Cell {
+                grid: &self.current,
+                neighborhood,
+                x,
+                y,
+            });
+            changed = changed || value != *self.current.get(x, y).unwrap();
+            self.next.set(x, y, value);
+        }
+        core::mem::swap(&mut self.current, &mut self.next);
+        changed
+    }
+
+    pub fn run<F>(
+        &mut self,
+        steps: usize,
+        neighborhood: Neighborhood,
+        mut rule: F,
+    ) where
+        F: FnMut(Cell<'_, T>) -> T,
+    {
+        for _ in 0..steps {
+            self.step(neighborhood, &mut rule);
+        }
+    }
+
+    // Steps until no cell changes, returning the number of steps which
+    // changed the grid.
+    pub fn run_until_stable<F>(
+        &mut self,
+        neighborhood: Neighborhood,
+        mut rule: F,
+    ) -> usize
+    where
+        F: FnMut(Cell<'_, T>) -> T,
+    {
+        let mut steps = 0;
+        while self.step(neighborhood, &mut rule) {
+            steps += 1;
+        }
+        steps
+    }
+
+    // Moves every cell for which `falls` returns true as far as it can in the
+    // direction of `gravity` through cells for which `open` returns true.
+    // Cells are processed starting from the edge they fall towards, so a
+    // single sweep settles the whole grid. Returns whether any cell moved.
+    pub fn sweep<F, G>(
+        &mut self,
+        gravity: Gravity,
+        mut falls: F,
+        mut open: G,
+    ) -> bool
+    where
+        F: FnMut(&T) -> bool,
+        G: FnMut(&T) -> bool,
+    {
+        let width = self.current.width();
+        let height = self.current.height();
+        let (lines, length) = match gravity {
+            Gravity::Up | Gravity::Down => (width, height),
+            Gravity::Left | Gravity::Right => (height, width),
+        };
+        // Position `i` along a line counts back from the edge cells fall
+        // towards
+        let position = |line: usize, i: usize| match gravity {
+            Gravity::Up => (line, i),
+            Gravity::Down => (line, height - 1 - i),
+            Gravity::Left => (i, line),
+            Gravity::Right => (width - 1 - i, line),
+        };
+
+        let mut moved = false;
+        for line in 0..lines {
+            let mut landing = None;
+            for i in 0..length {
+                let (x, y) = position(line, i);
+                let cell = self.current.get(x, y).unwrap();
+                if open(cell) {
+                    landing.get_or_insert(i);
+                } else if falls(cell) {
+                    if let Some(l) = landing {
+                        self.current.swap(position(line, l), (x, y));
+                        landing = Some(l + 1);
+                        moved = true;
+                    }
+                } else {
+                    landing = None;
+                }
+            }
+        }
+        moved
+    }
+}
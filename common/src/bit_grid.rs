@@ -0,0 +1,258 @@
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not};
+
+use crate::{Grid, Region};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+// Each row is stored in its own run of words so that rows can be shifted
+// independently. Bits past the width of the grid are always kept clear.
+#[derive(Clone, Hash, PartialEq, Eq)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    stride: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(WORD_BITS);
+        Self {
+            width,
+            height,
+            stride,
+            words: vec![0; stride * height],
+        }
+    }
+
+    pub fn from_grid<T, F: FnMut(&T) -> bool>(
+        grid: &Grid<T>,
+        mut f: F,
+    ) -> Self {
+        let mut result = Self::new(grid.width(), grid.height());
+        for (x, y) in grid.iter() {
+            if f(grid.get(x, y).unwrap()) {
+                result.set(x, y, true);
+            }
+        }
+        result
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<bool> {
+        if x < self.width && y < self.height {
+            let word = self.words[y * self.stride + x / WORD_BITS];
+            Some(word & 1 << (x % WORD_BITS) != 0)
+        } else {
+            None
+        }
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        assert!(x < self.width && y < self.height);
+        let word = &mut self.words[y * self.stride + x / WORD_BITS];
+        if value {
+            *word |= 1 << (x % WORD_BITS);
+        } else {
+            *word &= !(1 << (x % WORD_BITS));
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    pub fn region(&self) -> Region {
+        Region::new(0, 0, self.width, self.height)
+    }
+
+    // Iterates over the coordinates of every set bit in row-major order.
+    pub fn iter(&self) -> Ones<'_> {
+        Ones {
+            grid: self,
+            index: 0,
+            word: self.words.first().copied().unwrap_or(0),
+        }
+    }
+
+    // Moves every bit by `(dx, dy)`. Bits shifted off the edge are discarded
+    // and bits shifted in are clear.
+    pub fn shifted(&self, dx: isize, dy: isize) -> Self {
+        let mut result = Self::new(self.width, self.height);
+        for y in 0..self.height {
+            let Some(sy) =
+                y.checked_add_signed(-dy).filter(|&sy| sy < self.height)
+            else {
+                continue;
+            };
+
+            let src = &self.words[sy * self.stride..(sy + 1) * self.stride];
+            let dest =
+                &mut result.words[y * self.stride..(y + 1) * self.stride];
+            shift_row(src, dest, dx);
+        }
+        result.mask_padding();
+        result
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self | other
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self & other
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        result.zip_words(other, |a, b| a & !b);
+        result
+    }
+
+    // Returns the cells orthogonally adjacent to any set cell.
+    pub fn orthogonal_neighbors(&self) -> Self {
+        let mut result = self.shifted(1, 0);
+        result |= &self.shifted(-1, 0);
+        result |= &self.shifted(0, 1);
+        result |= &self.shifted(0, -1);
+        result
+    }
+
+    pub fn to_grid(&self) -> Grid<bool> {
+        Grid::from_elements(
+            self.width,
+            self.height,
+            self.region()
+                .map(|(x, y)| self.get(x, y).unwrap())
+                .collect(),
+        )
+    }
+
+    fn zip_words<F: FnMut(u64, u64) -> u64>(&mut self, other: &Self, mut f: F) {
+        assert!(self.width == other.width && self.height == other.height);
+        for (a, b) in self.words.iter_mut().zip(other.words.iter()) {
+            *a = f(*a, *b);
+        }
+    }
+
+    fn mask_padding(&mut self) {
+        let used = self.width % WORD_BITS;
+        if used != 0 {
+            let mask = (1 << used) - 1;
+            for row in self.words.chunks_exact_mut(self.stride) {
+                *row.last_mut().unwrap() &= mask;
+            }
+        }
+    }
+}
+
+fn shift_row(src: &[u64], dest: &mut [u64], dx: isize) {
+    let words = dx.unsigned_abs() / WORD_BITS;
+    let bits = dx.unsigned_abs() % WORD_BITS;
+    let len = src.len();
+
+    for (i, d) in dest.iter_mut().enumerate() {
+        *d = if dx >= 0 {
+            let hi = i.checked_sub(words).map_or(0, |j| src[j]);
+            let lo = i.checked_sub(words + 1).map_or(0, |j| src[j]);
+            if bits == 0 {
+                hi
+            } else {
+                hi << bits | lo >> (WORD_BITS - bits)
+            }
+        } else {
+            let lo = src.get(i + words).copied().unwrap_or(0);
+            let hi = if i + words + 1 < len {
+                src[i + words + 1]
+            } else {
+                0
+            };
+            if bits == 0 {
+                lo
+            } else {
+                lo >> bits | hi << (WORD_BITS - bits)
+            }
+        };
+    }
+}
+
+pub struct Ones<'a> {
+    grid: &'a BitGrid,
+    index: usize,
+    word: u64,
+}
+
+impl Iterator for Ones<'_> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.word == 0 {
+            self.index += 1;
+            self.word = *self.grid.words.get(self.index)?;
+        }
+
+        let bit = self.word.trailing_zeros() as usize;
+        self.word &= self.word - 1;
+
+        let stride = self.grid.stride;
+        Some((self.index % stride * WORD_BITS + bit, self.index / stride))
+    }
+}
+
+impl BitOrAssign<&BitGrid> for BitGrid {
+    fn bitor_assign(&mut self, rhs: &BitGrid) {
+        self.zip_words(rhs, |a, b| a | b);
+    }
+}
+
+impl BitAndAssign<&BitGrid> for BitGrid {
+    fn bitand_assign(&mut self, rhs: &BitGrid) {
+        self.zip_words(rhs, |a, b| a & b);
+    }
+}
+
+impl BitOr for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        result |= rhs;
+        result
+    }
+}
+
+impl BitAnd for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        result &= rhs;
+        result
+    }
+}
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> Self::Output {
+        let mut result = self.clone();
+        result.words.iter_mut().for_each(|w| *w = !*w);
+        result.mask_padding();
+        result
+    }
+}
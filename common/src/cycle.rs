@@ -0,0 +1,192 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
+
+// The shape of the sequence produced by repeatedly stepping a state: `tail`
+// steps lead into a loop of `length` states.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub tail: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    // Returns the earliest step which has the same state as step `n`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.tail {
+            n
+        } else {
+            self.tail + (n - self.tail) % self.length
+        }
+    }
+}
+
+pub fn fingerprint<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+// Floyd's tortoise and hare. Only ever holds two states at once.
+pub fn floyd<S: Clone + Eq, F: FnMut(&mut S)>(start: &S, mut step: F) -> Cycle {
+    let mut tortoise = start.clone();
+    let mut hare = start.clone();
+    loop {
+        step(&mut tortoise);
+        step(&mut hare);
+        step(&mut hare);
+        if tortoise == hare {
+            break;
+        }
+    }
+
+    let mut tail = 0;
+    tortoise = start.clone();
+    while tortoise != hare {
+        step(&mut tortoise);
+        step(&mut hare);
+        tail += 1;
+    }
+
+    let mut length = 1;
+    step(&mut hare);
+    while tortoise != hare {
+        step(&mut hare);
+        length += 1;
+    }
+
+    Cycle { tail, length }
+}
+
+// Brent's algorithm. Takes fewer steps than Floyd's on average.
+pub fn brent<S: Clone + Eq, F: FnMut(&mut S)>(start: &S, mut step: F) -> Cycle {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = start.clone();
+    step(&mut hare);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        step(&mut hare);
+        length += 1;
+    }
+
+    let mut tail = 0;
+    tortoise = start.clone();
+    hare = start.clone();
+    for _ in 0..length {
+        step(&mut hare);
+    }
+    while tortoise != hare {
+        step(&mut tortoise);
+        step(&mut hare);
+        tail += 1;
+    }
+
+    Cycle { tail, length }
+}
+
+// Steps from `start` until a state repeats or `limit` steps have been taken,
+// returning the state reached, the number of steps taken and the cycle if
+// one was found. Only the fingerprint of each state's key is remembered, so a
+// matching fingerprint is confirmed by replaying the earlier state from
+// `start` and comparing the keys exactly.
+fn search<S, K, F, G>(
+    start: S,
+    limit: usize,
+    step: &mut F,
+    key: &G,
+) -> (S, usize, Option<Cycle>)
+where
+    S: Clone,
+    K: Eq + Hash + ?Sized,
+    F: FnMut(&mut S),
+    G: Fn(&S) -> &K,
+{
+    let origin = start.clone();
+    let mut seen = HashMap::<u64, Vec<usize>>::new();
+    let mut state = start;
+    for i in 0..limit {
+        let steps = seen.entry(fingerprint(key(&state))).or_default();
+
+        // Earlier states are all different, so at most one can match
+        if !steps.is_empty() {
+            let mut earlier = origin.clone();
+            let mut at = 0;
+            for &tail in steps.iter() {
+                for _ in at..tail {
+                    step(&mut earlier);
+                }
+                at = tail;
+                if key(&earlier) == key(&state) {
+                    let cycle = Cycle {
+                        tail,
+                        length: i - tail,
+                    };
+                    return (state, i, Some(cycle));
+                }
+            }
+        }
+
+        steps.push(i);
+        step(&mut state);
+    }
+    (state, limit, None)
+}
+
+// Detects a cycle by remembering a fingerprint of the key of every state
+// seen, where states with equal keys are the same state. Returns the cycle
+// along with the state at step `tail + length`.
+pub fn detect_by<S, K, F, G>(start: S, mut step: F, key: G) -> (Cycle, S)
+where
+    S: Clone,
+    K: Eq + Hash + ?Sized,
+    F: FnMut(&mut S),
+    G: Fn(&S) -> &K,
+{
+    match search(start, usize::MAX, &mut step, &key) {
+        (state, _, Some(cycle)) => (cycle, state),
+        (_, _, None) => unreachable!("ran out of steps without a cycle"),
+    }
+}
+
+// As `detect_by`, using each state as its own key.
+pub fn detect<S, F>(start: S, step: F) -> (Cycle, S)
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&mut S),
+{
+    detect_by(start, step, |state| state)
+}
+
+// Returns the state after `n` steps, skipping over whole cycles once one is
+// detected. States with equal keys are the same state.
+pub fn state_after_by<S, K, F, G>(start: S, n: usize, mut step: F, key: G) -> S
+where
+    S: Clone,
+    K: Eq + Hash + ?Sized,
+    F: FnMut(&mut S),
+    G: Fn(&S) -> &K,
+{
+    let (mut state, i, cycle) = search(start, n, &mut step, &key);
+    if let Some(Cycle { length, .. }) = cycle {
+        for _ in 0..(n - i) % length {
+            step(&mut state);
+        }
+    }
+    state
+}
+
+// Returns the state after `n` steps, using each state as its own key.
+pub fn state_after<S, F>(start: S, n: usize, step: F) -> S
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&mut S),
+{
+    state_after_by(start, n, step, |state| state)
+}
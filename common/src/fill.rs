@@ -0,0 +1,156 @@
+use crate::{grid::orthogonal, BitGrid, Grid};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Component {
+    pub area: usize,
+    // The number of cell edges which border a cell outside of the component,
+    // including the edge of the grid.
+    pub perimeter: usize,
+    pub min_x: usize,
+    pub min_y: usize,
+    pub max_x: usize,
+    pub max_y: usize,
+}
+
+pub struct Components {
+    // The index of the component each cell belongs to, if any.
+    pub labels: Grid<Option<usize>>,
+    pub components: Vec<Component>,
+}
+
+fn flood<F: FnMut(usize, usize) -> bool>(
+    width: usize,
+    height: usize,
+    starts: impl IntoIterator<Item = (usize, usize)>,
+    mut passable: F,
+) -> BitGrid {
+    let mut result = BitGrid::new(width, height);
+    let mut stack = Vec::new();
+
+    for (x, y) in starts {
+        if passable(x, y) && !result.get(x, y).unwrap() {
+            result.set(x, y, true);
+            stack.push((x, y));
+        }
+    }
+
+    while let Some((x, y)) = stack.pop() {
+        for (nx, ny) in orthogonal(width, height, x, y) {
+            if !result.get(nx, ny).unwrap() && passable(nx, ny) {
+                result.set(nx, ny, true);
+                stack.push((nx, ny));
+            }
+        }
+    }
+
+    result
+}
+
+impl<T> Grid<T> {
+    // Returns every cell orthogonally connected to `(x, y)` through passable
+    // cells. The result is empty if the start cell is not passable.
+    pub fn flood_fill<F: FnMut(&T) -> bool>(
+        &self,
+        x: usize,
+        y: usize,
+        mut passable: F,
+    ) -> BitGrid {
+        flood(self.width(), self.height(), [(x, y)], |x, y| {
+            passable(self.get(x, y).unwrap())
+        })
+    }
+
+    // Labels each orthogonally connected group of cells which satisfy
+    // `predicate`.
+    pub fn components<F: FnMut(&T) -> bool>(
+        &self,
+        mut predicate: F,
+    ) -> Components {
+        let included = BitGrid::from_grid(self, |t| predicate(t));
+        let mut labels =
+            Grid::<Option<usize>>::default(self.width(), self.height());
+        let mut components = Vec::new();
+        let mut stack = Vec::new();
+
+        for (sx, sy) in included.iter() {
+            if labels.get(sx, sy).unwrap().is_some() {
+                continue;
+            }
+
+            let label = components.len();
+            let mut component = Component {
+                area: 0,
+                perimeter: 0,
+                min_x: sx,
+                min_y: sy,
+                max_x: sx,
+                max_y: sy,
+            };
+
+            labels.set(sx, sy, Some(label));
+            stack.push((sx, sy));
+            while let Some((x, y)) = stack.pop() {
+                component.area += 1;
+                component.perimeter += 4;
+                component.min_x = usize::min(component.min_x, x);
+                component.min_y = usize::min(component.min_y, y);
+                component.max_x = usize::max(component.max_x, x);
+                component.max_y = usize::max(component.max_y, y);
+
+                for (nx, ny) in self.orthogonal(x, y) {
+                    if included.get(nx, ny).unwrap() {
+                        component.perimeter -= 1;
+                        if labels.get(nx, ny).unwrap().is_none() {
+                            labels.set(nx, ny, Some(label));
+                            stack.push((nx, ny));
+                        }
+                    }
+                }
+            }
+
+            components.push(component);
+        }
+
+        Components { labels, components }
+    }
+}
+
+impl BitGrid {
+    // Treats the set cells as a boundary and returns the cells it encloses.
+    // Adjacent boundary cells only form a wall between them if `joined`
+    // returns true for them, so unjoined boundary cells leave a gap that the
+    // outside can squeeze through.
+    pub fn enclosed<F>(&self, mut joined: F) -> BitGrid
+    where
+        F: FnMut((usize, usize), (usize, usize)) -> bool,
+    {
+        // Cell (x, y) is at (2x + 1, 2y + 1) in the expanded grid, and the
+        // space between adjacent cells is at their midpoint.
+        let width = 2 * self.width() + 1;
+        let height = 2 * self.height() + 1;
+        let mut walls = BitGrid::new(width, height);
+
+        for (x, y) in self.iter() {
+            walls.set(2 * x + 1, 2 * y + 1, true);
+            if self.get(x + 1, y) == Some(true) && joined((x, y), (x + 1, y)) {
+                walls.set(2 * x + 2, 2 * y + 1, true);
+            }
+            if self.get(x, y + 1) == Some(true) && joined((x, y), (x, y + 1)) {
+                walls.set(2 * x + 1, 2 * y + 2, true);
+            }
+        }
+
+        let outside =
+            flood(width, height, [(0, 0)], |x, y| !walls.get(x, y).unwrap());
+
+        let mut result = BitGrid::new(self.width(), self.height());
+        for (x, y) in self.region() {
+            if !self.get(x, y).unwrap()
+                && !outside.get(2 * x + 1, 2 * y + 1).unwrap()
+            {
+                result.set(x, y, true);
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{bail, math::gcd, num::Rational, Result, Vec2};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+// A simple polygon with vertices on integer lattice points. The last vertex
+// connects back to the first.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Polygon {
+    vertices: Vec<Vec2<i64>>,
+}
+
+impl Polygon {
+    pub fn from_vertices(vertices: Vec<Vec2<i64>>) -> Self {
+        Self { vertices }
+    }
+
+    // Builds a polygon by starting at the origin and repeatedly moving some
+    // distance along a direction, which must bring the walk back to the
+    // origin.
+    pub fn from_walk(
+        moves: impl IntoIterator<Item = (Vec2<i64>, i64)>,
+    ) -> Result<Self> {
+        let mut vertices = Vec::new();
+        let mut position = Vec2::default();
+        for (direction, distance) in moves {
+            vertices.push(position);
+            position += direction * distance;
+        }
+
+        if position != Vec2::default() {
+            bail!("walk ends at {position} instead of returning to the origin");
+        }
+
+        Ok(Self { vertices })
+    }
+
+    pub fn vertices(&self) -> &[Vec2<i64>] {
+        &self.vertices
+    }
+
+    // Each edge as a pair of endpoints, widened so that products of
+    // coordinates cannot overflow.
+    fn edges(&self) -> impl Iterator<Item = (Vec2<i128>, Vec2<i128>)> + '_ {
+        let wide = |v: &Vec2<i64>| v.map(i128::from);
+        self.vertices
+            .iter()
+            .map(wide)
+            .zip(self.vertices.iter().map(wide).cycle().skip(1))
+    }
+
+    // Twice the signed area, from the shoelace formula.
+    fn doubled_area(&self) -> i128 {
+        self.edges().map(|(a, b)| a.cross(b)).sum()
+    }
+
+    // The area enclosed by the polygon. Positive when the vertices wind
+    // counterclockwise in a y-up coordinate system.
+    pub fn signed_area(&self) -> Rational<i128> {
+        Rational::new(self.doubled_area(), 2)
+    }
+
+    pub fn area(&self) -> Rational<i128> {
+        Rational::new(self.doubled_area().abs(), 2)
+    }
+
+    // The number of lattice points on the edges of the polygon.
+    pub fn boundary_points(&self) -> i128 {
+        self.edges()
+            .map(|(a, b)| {
+                let d = b - a;
+                gcd(d.x, d.y)
+            })
+            .sum()
+    }
+
+    // The number of lattice points strictly inside the polygon, from Pick's
+    // theorem: A = I + B / 2 - 1.
+    pub fn interior_points(&self) -> i128 {
+        (self.doubled_area().abs() - self.boundary_points() + 2) / 2
+    }
+
+    pub fn locate(&self, point: Vec2<i64>) -> Location {
+        let p = point.map(i128::from);
+
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            let cross = (p - a).cross(b - a);
+            let (low, high) = (a.component_min(b), a.component_max(b));
+            if cross == 0
+                && low.x <= p.x
+                && p.x <= high.x
+                && low.y <= p.y
+                && p.y <= high.y
+            {
+                return Location::Boundary;
+            }
+
+            // Cast a ray toward +x and count the edges it crosses
+            if (a.y > p.y) != (b.y > p.y) && (cross < 0) == (b.y > a.y) {
+                inside = !inside;
+            }
+        }
+
+        if inside {
+            Location::Inside
+        } else {
+            Location::Outside
+        }
+    }
+
+    // Whether the point is inside the polygon or on its boundary.
+    pub fn contains(&self, point: Vec2<i64>) -> bool {
+        self.locate(point) != Location::Outside
+    }
+}
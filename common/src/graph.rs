@@ -0,0 +1,318 @@
+use std::{
+    borrow::Borrow,
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+    ops::Range,
+};
+
+mod cut;
+mod dag;
+mod dot;
+
+pub use self::{
+    cut::{Capacity, Cut, Flow},
+    dag::Condensation,
+    dot::Dot,
+};
+
+// Collects labeled nodes and weighted edges before packing them into a
+// `Graph`. Nodes are numbered in the order they are first seen.
+#[derive(Clone, Debug)]
+pub struct GraphBuilder<L, W = ()> {
+    labels: Vec<L>,
+    ids: HashMap<L, usize>,
+    edges: Vec<(usize, usize, W)>,
+}
+
+impl<L: Clone + Hash + Eq, W> GraphBuilder<L, W> {
+    pub fn new() -> Self {
+        Self {
+            labels: Vec::new(),
+            ids: HashMap::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    // Returns the id of the node with the given label, adding it if it has
+    // not been seen yet.
+    pub fn node<Q>(&mut self, label: &Q) -> usize
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq + ToOwned<Owned = L> + ?Sized,
+    {
+        if let Some(&id) = self.ids.get(label) {
+            return id;
+        }
+
+        let id = self.labels.len();
+        self.labels.push(label.to_owned());
+        self.ids.insert(label.to_owned(), id);
+        id
+    }
+
+    pub fn id<Q>(&self, label: &Q) -> Option<usize>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.ids.get(label).copied()
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: W) {
+        assert!(from < self.labels.len() && to < self.labels.len());
+        self.edges.push((from, to, weight));
+    }
+
+    // Adds an edge between two labeled nodes, adding the nodes if needed.
+    pub fn add_labeled_edge<Q>(&mut self, from: &Q, to: &Q, weight: W)
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq + ToOwned<Owned = L> + ?Sized,
+    {
+        let from = self.node(from);
+        let to = self.node(to);
+        self.add_edge(from, to, weight);
+    }
+
+    // Each edge leads only from its first node to its second.
+    pub fn directed(self) -> Graph<L, W> {
+        Graph::from_arcs(true, self.labels, self.ids, self.edges)
+    }
+
+    // Each edge can be followed in either direction.
+    pub fn undirected(self) -> Graph<L, W>
+    where
+        W: Clone,
+    {
+        let mut arcs = Vec::with_capacity(2 * self.edges.len());
+        for (from, to, weight) in self.edges {
+            if from != to {
+                arcs.push((to, from, weight.clone()));
+            }
+            arcs.push((from, to, weight));
+        }
+        Graph::from_arcs(false, self.labels, self.ids, arcs)
+    }
+}
+
+impl<L: Clone + Hash + Eq, W> Default for GraphBuilder<L, W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// A graph stored in compressed sparse row form: the edges leaving each node
+// are contiguous, in the order they were added. An undirected graph stores
+// every edge in both directions.
+#[derive(Clone, Debug)]
+pub struct Graph<L, W = ()> {
+    directed: bool,
+    labels: Vec<L>,
+    ids: HashMap<L, usize>,
+    // The edges leaving node `i` are at `offsets[i]..offsets[i + 1]`
+    offsets: Vec<usize>,
+    targets: Vec<usize>,
+    weights: Vec<W>,
+}
+
+impl<L, W> Graph<L, W> {
+    fn from_arcs(
+        directed: bool,
+        labels: Vec<L>,
+        ids: HashMap<L, usize>,
+        arcs: Vec<(usize, usize, W)>,
+    ) -> Self {
+        let mut offsets = vec![0; labels.len() + 1];
+        for &(from, _, _) in arcs.iter() {
+            offsets[from + 1] += 1;
+        }
+        for i in 1..offsets.len() {
+            offsets[i] += offsets[i - 1];
+        }
+
+        // Place each arc with a counting sort, which keeps the arcs from each
+        // node in insertion order
+        let mut next = offsets.clone();
+        let mut slots = Vec::new();
+        slots.resize_with(arcs.len(), || None);
+        for (from, to, weight) in arcs {
+            slots[next[from]] = Some((to, weight));
+            next[from] += 1;
+        }
+        let (targets, weights) = slots.into_iter().map(Option::unwrap).unzip();
+
+        Self {
+            directed,
+            labels,
+            ids,
+            offsets,
+            targets,
+            weights,
+        }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn nodes(&self) -> Range<usize> {
+        0..self.labels.len()
+    }
+
+    pub fn label(&self, node: usize) -> &L {
+        &self.labels[node]
+    }
+
+    pub fn labels(&self) -> &[L] {
+        &self.labels
+    }
+
+    pub fn id<Q>(&self, label: &Q) -> Option<usize>
+    where
+        L: Borrow<Q> + Hash + Eq,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.ids.get(label).copied()
+    }
+
+    fn arcs(&self, node: usize) -> Range<usize> {
+        self.offsets[node]..self.offsets[node + 1]
+    }
+
+    pub fn degree(&self, node: usize) -> usize {
+        self.arcs(node).len()
+    }
+
+    pub fn neighbors(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+        self.targets[self.arcs(node)].iter().copied()
+    }
+
+    // The nodes reachable along an edge from `node`, paired with the weight
+    // of that edge.
+    pub fn edges_from(
+        &self,
+        node: usize,
+    ) -> impl Iterator<Item = (usize, &W)> + '_ {
+        let arcs = self.arcs(node);
+        self.targets[arcs.clone()]
+            .iter()
+            .copied()
+            .zip(self.weights[arcs].iter())
+    }
+
+    // Every edge as `(from, to, weight)`. Edges of an undirected graph are
+    // only yielded once, with `from <= to`.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, &W)> + '_ {
+        self.nodes()
+            .flat_map(move |from| {
+                self.edges_from(from).map(move |(to, w)| (from, to, w))
+            })
+            .filter(|&(from, to, _)| self.directed || from <= to)
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges().count()
+    }
+
+    pub fn map_weights<U>(&self, f: impl FnMut(&W) -> U) -> Graph<L, U>
+    where
+        L: Clone,
+    {
+        Graph {
+            directed: self.directed,
+            labels: self.labels.clone(),
+            ids: self.ids.clone(),
+            offsets: self.offsets.clone(),
+            targets: self.targets.clone(),
+            weights: self.weights.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<L: Clone, W: Clone> Graph<L, W> {
+    fn all_arcs(&self) -> Vec<(usize, usize, W)> {
+        self.nodes()
+            .flat_map(|from| {
+                self.edges_from(from)
+                    .map(move |(to, w)| (from, to, w.clone()))
+            })
+            .collect()
+    }
+
+    // Treats every undirected edge as a pair of opposing directed edges.
+    pub fn to_directed(&self) -> Self {
+        Self {
+            directed: true,
+            ..self.clone()
+        }
+    }
+
+    // Joins each pair of nodes with an edge in either direction by a single
+    // undirected edge, keeping the largest weight among the edges joined.
+    pub fn to_undirected(&self) -> Self
+    where
+        W: Ord,
+    {
+        if !self.directed {
+            return self.clone();
+        }
+
+        // The position of each pair in `edges`, in order of first appearance
+        let mut seen = HashMap::<_, usize>::new();
+        let mut edges = Vec::<(usize, usize, W)>::new();
+        for (from, to, weight) in self.all_arcs() {
+            match seen.entry((from.min(to), from.max(to))) {
+                Entry::Occupied(entry) => {
+                    let best = &mut edges[*entry.get()].2;
+                    if weight > *best {
+                        *best = weight;
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(edges.len());
+                    edges.push((from, to, weight));
+                }
+            }
+        }
+
+        let mut arcs = Vec::new();
+        for (from, to, weight) in edges {
+            if from != to {
+                arcs.push((to, from, weight.clone()));
+            }
+            arcs.push((from, to, weight));
+        }
+        Self::from_arcs(false, self.labels.clone(), self.ids.clone(), arcs)
+    }
+
+    // Flips the direction of every edge.
+    pub fn reversed(&self) -> Self {
+        let arcs = self
+            .all_arcs()
+            .into_iter()
+            .map(|(from, to, weight)| (to, from, weight))
+            .collect();
+        Self::from_arcs(
+            self.directed,
+            self.labels.clone(),
+            self.ids.clone(),
+            arcs,
+        )
+    }
+}
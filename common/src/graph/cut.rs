@@ -0,0 +1,277 @@
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+use super::Graph;
+
+// An edge weight which can be used as the capacity of the edge.
+pub trait Capacity {
+    fn capacity(&self) -> u64;
+}
+
+// Unweighted edges each have a capacity of one.
+impl Capacity for () {
+    fn capacity(&self) -> u64 {
+        1
+    }
+}
+
+macro_rules! impl_capacity {
+    ($($ty:ty),*) => {
+        $(
+            impl Capacity for $ty {
+                fn capacity(&self) -> u64 {
+                    (*self).try_into().expect("capacity out of range")
+                }
+            }
+        )*
+    };
+}
+
+impl_capacity!(u8, u16, u32, u64, usize, i32, i64);
+
+// A partition of the nodes of a graph into two sides, along with the edges
+// which cross between them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cut {
+    // The total capacity of the cut edges
+    pub size: u64,
+    pub edges: Vec<(usize, usize)>,
+    pub sides: [Vec<usize>; 2],
+}
+
+// A maximum flow from a source to a sink. Each entry of `flows` is the net
+// flow along the corresponding edge of `Graph::edges`, which is negative when
+// an undirected edge carries flow from its second node to its first. `cut` is
+// a minimum cut with the source on the first side.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Flow {
+    pub value: u64,
+    pub flows: Vec<i128>,
+    pub cut: Cut,
+}
+
+impl<L, W: Capacity> Graph<L, W> {
+    fn cut_from_side(&self, in_first: &[bool]) -> Cut {
+        let mut size = 0;
+        let mut edges = Vec::new();
+        for (from, to, weight) in self.edges() {
+            if in_first[from] && !in_first[to]
+                || !self.directed && !in_first[from] && in_first[to]
+            {
+                size += weight.capacity();
+                edges.push((from, to));
+            }
+        }
+
+        let (first, second) = self.nodes().partition(|&node| in_first[node]);
+        Cut {
+            size,
+            edges,
+            sides: [first, second],
+        }
+    }
+
+    // Finds a cut of the smallest total capacity which splits the graph into
+    // two non-empty sides, using the Stoer-Wagner algorithm. Requires an
+    // undirected graph with at least two nodes.
+    pub fn min_cut(&self) -> Cut {
+        assert!(!self.directed, "minimum cut requires an undirected graph");
+        assert!(self.len() >= 2, "minimum cut requires at least two nodes");
+
+        // Nodes are merged together as the algorithm runs. Each merged node
+        // keeps the original nodes it contains and its total capacity to
+        // every other merged node.
+        let mut members =
+            self.nodes().map(|node| vec![node]).collect::<Vec<_>>();
+        let mut adjacent = vec![HashMap::<usize, u64>::new(); self.len()];
+        for (from, to, weight) in self.edges() {
+            if from != to {
+                *adjacent[from].entry(to).or_default() += weight.capacity();
+                *adjacent[to].entry(from).or_default() += weight.capacity();
+            }
+        }
+        let mut active = self.nodes().collect::<Vec<_>>();
+
+        let mut best: Option<(u64, Vec<usize>)> = None;
+        let mut weights = vec![0; self.len()];
+        let mut added = vec![false; self.len()];
+        while active.len() > 1 {
+            // Add nodes in order of how strongly they connect to the nodes
+            // already added
+            for &node in active.iter() {
+                weights[node] = 0;
+                added[node] = false;
+            }
+            let mut queue = active
+                .iter()
+                .map(|&node| (0, node))
+                .collect::<BinaryHeap<_>>();
+            let mut order = Vec::with_capacity(active.len());
+            while let Some((weight, node)) = queue.pop() {
+                if added[node] || weight != weights[node] {
+                    continue;
+                }
+                added[node] = true;
+                order.push(node);
+                for (&next, &capacity) in adjacent[node].iter() {
+                    if !added[next] {
+                        weights[next] += capacity;
+                        queue.push((weights[next], next));
+                    }
+                }
+            }
+
+            // The last node added is separated from the rest by a minimum
+            // cut between it and the node added before it
+            let t = order.pop().unwrap();
+            let s = *order.last().unwrap();
+            if best.as_ref().is_none_or(|(size, _)| weights[t] < *size) {
+                best = Some((weights[t], members[t].clone()));
+            }
+
+            // Merge the last node into the one before it
+            let merged = std::mem::take(&mut adjacent[t]);
+            for (next, capacity) in merged {
+                adjacent[next].remove(&t);
+                if next != s {
+                    *adjacent[s].entry(next).or_default() += capacity;
+                    *adjacent[next].entry(s).or_default() += capacity;
+                }
+            }
+            let moved = std::mem::take(&mut members[t]);
+            members[s].extend(moved);
+            active.retain(|&node| node != t);
+        }
+
+        let (_, side) = best.unwrap();
+        let mut in_first = vec![false; self.len()];
+        for node in side {
+            in_first[node] = true;
+        }
+        self.cut_from_side(&in_first)
+    }
+
+    // Finds a maximum flow from `source` to `sink` with Dinic's algorithm.
+    // Edges of an undirected graph can carry flow in either direction.
+    pub fn max_flow(&self, source: usize, sink: usize) -> Flow {
+        assert_ne!(source, sink, "source and sink must be different");
+
+        // Residual arcs are stored in pairs so that `arc ^ 1` is the reverse
+        // of `arc`
+        let mut heads = vec![Vec::new(); self.len()];
+        let mut targets = Vec::new();
+        let mut residual = Vec::new();
+        for (from, to, weight) in self.edges() {
+            let capacity = weight.capacity();
+            heads[from].push(targets.len());
+            targets.push(to);
+            residual.push(capacity);
+            heads[to].push(targets.len());
+            targets.push(from);
+            residual.push(if self.directed { 0 } else { capacity });
+        }
+        let original = residual.clone();
+
+        let mut value = 0;
+        let mut levels = vec![usize::MAX; self.len()];
+        loop {
+            // Build the level graph with a breadth-first search
+            levels.fill(usize::MAX);
+            levels[source] = 0;
+            let mut queue = VecDeque::from([source]);
+            while let Some(node) = queue.pop_front() {
+                for &arc in heads[node].iter() {
+                    let next = targets[arc];
+                    if residual[arc] > 0 && levels[next] == usize::MAX {
+                        levels[next] = levels[node] + 1;
+                        queue.push_back(next);
+                    }
+                }
+            }
+            if levels[sink] == usize::MAX {
+                break;
+            }
+
+            // Push blocking flow along level-increasing paths
+            let mut cursors = vec![0; self.len()];
+            loop {
+                let pushed = augment(
+                    &heads,
+                    &targets,
+                    &mut residual,
+                    &levels,
+                    &mut cursors,
+                    source,
+                    sink,
+                );
+                if pushed == 0 {
+                    break;
+                }
+                value += pushed;
+            }
+        }
+
+        // Net flow along each edge, in `edges` order
+        let flows = (0..targets.len() / 2)
+            .map(|edge| {
+                let forward = 2 * edge;
+                original[forward] as i128 - residual[forward] as i128
+            })
+            .collect();
+
+        let reachable =
+            levels.iter().map(|&l| l != usize::MAX).collect::<Vec<_>>();
+        Flow {
+            value,
+            flows,
+            cut: self.cut_from_side(&reachable),
+        }
+    }
+}
+
+// Finds one path from `source` to `sink` through the level graph and pushes
+// as much flow along it as possible. Returns the amount pushed.
+fn augment(
+    heads: &[Vec<usize>],
+    targets: &[usize],
+    residual: &mut [u64],
+    levels: &[usize],
+    cursors: &mut [usize],
+    source: usize,
+    sink: usize,
+) -> u64 {
+    let mut path = Vec::new();
+    let mut node = source;
+    loop {
+        if node == sink {
+            let pushed = path.iter().map(|&arc| residual[arc]).min().unwrap();
+            for &arc in path.iter() {
+                residual[arc] -= pushed;
+                residual[arc ^ 1] += pushed;
+            }
+            return pushed;
+        }
+
+        let next_arc = heads[node][cursors[node]..].iter().position(|&arc| {
+            residual[arc] > 0 && levels[targets[arc]] == levels[node] + 1
+        });
+        match next_arc {
+            Some(offset) => {
+                cursors[node] += offset;
+                let arc = heads[node][cursors[node]];
+                path.push(arc);
+                node = targets[arc];
+            }
+            None => {
+                // Dead end, so retreat and never try this node again
+                cursors[node] = heads[node].len();
+                match path.pop() {
+                    Some(arc) => {
+                        node = targets[arc ^ 1];
+                        cursors[node] += 1;
+                    }
+                    None => return 0,
+                }
+            }
+        }
+    }
+}
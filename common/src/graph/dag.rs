@@ -0,0 +1,200 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::Graph;
+use crate::{bail, search::Cost, Result};
+
+// The strongly connected components of a directed graph, each collapsed into
+// a single node. The condensed graph is acyclic and keeps every edge which
+// runs between different components.
+#[derive(Clone, Debug)]
+pub struct Condensation<W = ()> {
+    // The component containing each node of the original graph
+    pub components: Vec<usize>,
+    // Each node is labeled with the original nodes it contains
+    pub graph: Graph<Vec<usize>, W>,
+}
+
+impl<L, W> Graph<L, W> {
+    // Orders the nodes so that every edge leads forward, using Kahn's
+    // algorithm. Fails if the graph has a cycle.
+    pub fn toposort(&self) -> Result<Vec<usize>> {
+        if !self.directed {
+            bail!("only a directed graph can be sorted topologically");
+        }
+
+        let mut incoming = vec![0; self.len()];
+        for to in self.targets.iter() {
+            incoming[*to] += 1;
+        }
+
+        let mut ready = self
+            .nodes()
+            .filter(|&node| incoming[node] == 0)
+            .collect::<VecDeque<_>>();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(node) = ready.pop_front() {
+            order.push(node);
+            for next in self.neighbors(node) {
+                incoming[next] -= 1;
+                if incoming[next] == 0 {
+                    ready.push_back(next);
+                }
+            }
+        }
+
+        if order.len() != self.len() {
+            // Nodes left over may only be downstream of a cycle, so name one
+            // which is really on it
+            let node = self
+                .strongly_connected_components()
+                .into_iter()
+                .find(|component| match component[..] {
+                    [node] => self.neighbors(node).any(|next| next == node),
+                    _ => true,
+                })
+                .unwrap()[0];
+            bail!("graph has a cycle through node {node}");
+        }
+        Ok(order)
+    }
+
+    // Finds the strongly connected components with Tarjan's algorithm. The
+    // components are in topological order, so every edge between two
+    // components leads to a later one.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        const UNSEEN: usize = usize::MAX;
+
+        let mut index = vec![UNSEEN; self.len()];
+        let mut low = vec![0; self.len()];
+        let mut on_stack = vec![false; self.len()];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut next_index = 0;
+
+        for root in self.nodes() {
+            if index[root] != UNSEEN {
+                continue;
+            }
+
+            // Each frame is a node and the position of the next arc to try
+            let mut frames = vec![(root, self.offsets[root])];
+            index[root] = next_index;
+            low[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some(&mut (node, ref mut arc)) = frames.last_mut() {
+                if *arc < self.offsets[node + 1] {
+                    let next = self.targets[*arc];
+                    *arc += 1;
+                    if index[next] == UNSEEN {
+                        index[next] = next_index;
+                        low[next] = next_index;
+                        next_index += 1;
+                        stack.push(next);
+                        on_stack[next] = true;
+                        frames.push((next, self.offsets[next]));
+                    } else if on_stack[next] {
+                        low[node] = low[node].min(index[next]);
+                    }
+                    continue;
+                }
+
+                frames.pop();
+                if let Some(&(parent, _)) = frames.last() {
+                    low[parent] = low[parent].min(low[node]);
+                }
+                if low[node] == index[node] {
+                    let mut component = Vec::new();
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    component.reverse();
+                    components.push(component);
+                }
+            }
+        }
+
+        // Tarjan's algorithm finishes components in reverse topological order
+        components.reverse();
+        components
+    }
+
+    pub fn condensation(&self) -> Condensation<W>
+    where
+        W: Clone,
+    {
+        let members = self.strongly_connected_components();
+        let mut components = vec![0; self.len()];
+        for (id, component) in members.iter().enumerate() {
+            for &node in component.iter() {
+                components[node] = id;
+            }
+        }
+
+        let arcs = self
+            .edges()
+            .filter(|&(from, to, _)| components[from] != components[to])
+            .map(|(from, to, w)| (components[from], components[to], w.clone()))
+            .collect();
+        let ids = members
+            .iter()
+            .enumerate()
+            .map(|(id, component)| (component.clone(), id))
+            .collect::<HashMap<_, _>>();
+
+        Condensation {
+            components,
+            graph: Graph::from_arcs(true, members, ids, arcs),
+        }
+    }
+
+    // Relaxes the edges in topological order, keeping whichever distance
+    // `better` prefers.
+    fn dag_paths(
+        &self,
+        source: usize,
+        better: impl Fn(W, W) -> bool,
+    ) -> Result<Vec<Option<W>>>
+    where
+        W: Cost,
+    {
+        let mut distances = vec![None; self.len()];
+        distances[source] = Some(W::default());
+        for node in self.toposort()? {
+            let Some(distance) = distances[node] else {
+                continue;
+            };
+            for (next, &weight) in self.edges_from(node) {
+                let candidate = distance + weight;
+                if distances[next].is_none_or(|d| better(candidate, d)) {
+                    distances[next] = Some(candidate);
+                }
+            }
+        }
+        Ok(distances)
+    }
+
+    // The length of the longest path from `source` to every node, or `None`
+    // if the node can't be reached. Fails if the graph has a cycle.
+    pub fn longest_paths(&self, source: usize) -> Result<Vec<Option<W>>>
+    where
+        W: Cost,
+    {
+        self.dag_paths(source, |candidate, d| candidate > d)
+    }
+
+    // As `longest_paths`, but for the shortest path to each node.
+    pub fn shortest_paths(&self, source: usize) -> Result<Vec<Option<W>>>
+    where
+        W: Cost,
+    {
+        self.dag_paths(source, |candidate, d| candidate < d)
+    }
+}
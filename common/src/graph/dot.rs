@@ -0,0 +1,134 @@
+use std::{
+    collections::HashSet,
+    fmt::{self, Display, Formatter},
+};
+
+use super::Graph;
+
+type Format<'a, T> = Box<dyn Fn(&T) -> String + 'a>;
+
+// A graph prepared for drawing with Graphviz. Formatting it with `Display`
+// gives the graph in the DOT language.
+pub struct Dot<'a, L, W> {
+    graph: &'a Graph<L, W>,
+    // Nodes are labeled by id unless a format is given
+    label: Option<Format<'a, L>>,
+    weight: Option<Format<'a, W>>,
+    nodes: HashSet<usize>,
+    edges: HashSet<(usize, usize)>,
+}
+
+impl<'a, L, W> Dot<'a, L, W> {
+    pub fn labeled(self) -> Self
+    where
+        L: Display,
+    {
+        self.label_with(|label| label.to_string())
+    }
+
+    pub fn label_with(mut self, f: impl Fn(&L) -> String + 'a) -> Self {
+        self.label = Some(Box::new(f));
+        self
+    }
+
+    pub fn weighted(self) -> Self
+    where
+        W: Display,
+    {
+        self.weight_with(|weight| weight.to_string())
+    }
+
+    pub fn weight_with(mut self, f: impl Fn(&W) -> String + 'a) -> Self {
+        self.weight = Some(Box::new(f));
+        self
+    }
+
+    pub fn highlight_nodes(
+        mut self,
+        nodes: impl IntoIterator<Item = usize>,
+    ) -> Self {
+        self.nodes.extend(nodes);
+        self
+    }
+
+    // Edges of an undirected graph are highlighted in either direction.
+    pub fn highlight_edges(
+        mut self,
+        edges: impl IntoIterator<Item = (usize, usize)>,
+    ) -> Self {
+        for (from, to) in edges {
+            self.edges.insert((from, to));
+            if !self.graph.directed {
+                self.edges.insert((to, from));
+            }
+        }
+        self
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+const HIGHLIGHT: &str = "color=red, penwidth=2";
+
+impl<L, W> Display for Dot<'_, L, W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (kind, arrow) = if self.graph.directed {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+
+        writeln!(f, "{kind} {{")?;
+        for node in self.graph.nodes() {
+            let label = match &self.label {
+                Some(format) => format(self.graph.label(node)),
+                None => node.to_string(),
+            };
+            write!(f, "    {node} [label={}", quote(&label))?;
+            if self.nodes.contains(&node) {
+                write!(f, ", {HIGHLIGHT}")?;
+            }
+            writeln!(f, "];")?;
+        }
+
+        for (from, to, weight) in self.graph.edges() {
+            let mut attributes = Vec::new();
+            if let Some(weight_label) = &self.weight {
+                attributes
+                    .push(format!("label={}", quote(&weight_label(weight))));
+            }
+            if self.edges.contains(&(from, to)) {
+                attributes.push(HIGHLIGHT.to_string());
+            }
+
+            write!(f, "    {from} {arrow} {to}")?;
+            if !attributes.is_empty() {
+                write!(f, " [{}]", attributes.join(", "))?;
+            }
+            writeln!(f, ";")?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+impl<L, W> Graph<L, W> {
+    // Draws the graph with each node labeled by its id and no edge labels.
+    pub fn dot(&self) -> Dot<'_, L, W> {
+        Dot {
+            graph: self,
+            label: None,
+            weight: None,
+            nodes: HashSet::new(),
+            edges: HashSet::new(),
+        }
+    }
+
+    pub fn to_dot(&self) -> String
+    where
+        L: Display,
+    {
+        self.dot().labeled().to_string()
+    }
+}
@@ -0,0 +1,200 @@
+use std::io::BufRead;
+
+use crate::{bail, Context as _, Error, Input, Result};
+
+#[derive(Clone, Hash, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    elements: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_elements(
+        width: usize,
+        height: usize,
+        elements: Vec<T>,
+    ) -> Self {
+        assert_eq!(width * height, elements.len());
+
+        Self {
+            width,
+            height,
+            elements,
+        }
+    }
+
+    pub fn default(width: usize, height: usize) -> Self
+    where
+        T: Default,
+    {
+        Self {
+            width,
+            height,
+            elements: (0..width * height).map(|_| T::default()).collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn into_elements(self) -> Vec<T> {
+        self.elements
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height {
+            Some(&self.elements[x + y * self.width])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x < self.width && y < self.height {
+            Some(&mut self.elements[x + y * self.width])
+        } else {
+            None
+        }
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: T) {
+        assert!(x < self.width && y < self.height);
+        self.elements[x + y * self.width] = value;
+    }
+
+    pub fn swap(&mut self, a: (usize, usize), b: (usize, usize)) {
+        assert!(a.0 < self.width && a.1 < self.height);
+        assert!(b.0 < self.width && b.1 < self.height);
+        self.elements
+            .swap(a.0 + a.1 * self.width, b.0 + b.1 * self.width);
+    }
+
+    pub fn iter(&self) -> Region {
+        Region::new(0, 0, self.width(), self.height())
+    }
+
+    pub fn adjacent(&self, x: usize, y: usize) -> Region {
+        self.neighborhood(x, y, 1)
+    }
+
+    pub fn neighborhood(&self, x: usize, y: usize, radius: usize) -> Region {
+        let lx = x.saturating_sub(radius);
+        let ly = y.saturating_sub(radius);
+        let r = radius.saturating_add(1);
+        let ux = usize::min(x.saturating_add(r), self.width());
+        let uy = usize::min(y.saturating_add(r), self.height());
+
+        Region::new(lx, ly, ux - lx, uy - ly)
+    }
+
+    pub fn orthogonal(
+        &self,
+        x: usize,
+        y: usize,
+    ) -> impl Iterator<Item = (usize, usize)> {
+        orthogonal(self.width, self.height, x, y)
+    }
+
+    pub fn render<F: FnMut(&T) -> char>(&self, mut f: F) -> String {
+        let mut result = String::with_capacity((self.width + 1) * self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                result.push(f(&self.elements[x + y * self.width]));
+            }
+            result.push('\n');
+        }
+        result
+    }
+}
+
+pub(crate) fn orthogonal(
+    width: usize,
+    height: usize,
+    x: usize,
+    y: usize,
+) -> impl Iterator<Item = (usize, usize)> {
+    [(1, 0), (0, -1), (-1, 0), (0, 1)].into_iter().filter_map(
+        move |(dx, dy)| {
+            let nx = x.checked_add_signed(dx)?;
+            let ny = y.checked_add_signed(dy)?;
+            (nx < width && ny < height).then_some((nx, ny))
+        },
+    )
+}
+
+pub struct Region {
+    x: usize,
+    y: usize,
+    width: usize,
+    area: usize,
+    index: usize,
+}
+
+impl Region {
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            area: width * height,
+            index: 0,
+        }
+    }
+}
+
+impl Iterator for Region {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.area {
+            None
+        } else {
+            let result = Some((
+                self.x + self.index % self.width,
+                self.y + self.index / self.width,
+            ));
+
+            self.index += 1;
+
+            result
+        }
+    }
+}
+
+impl<T> Input for Grid<T>
+where
+    T: TryFrom<char>,
+    Error: From<T::Error>,
+{
+    fn parse_reader<R: BufRead>(reader: R) -> Result<Self> {
+        let mut width = None;
+        let mut height = 0;
+        let mut elements = Vec::new();
+
+        for line in reader.lines() {
+            let line = line?;
+
+            if width.is_some_and(|w| w != line.len()) {
+                bail!("uneven grid lines");
+            }
+            width = Some(line.len());
+            height += 1;
+
+            for c in line.chars() {
+                elements.push(T::try_from(c)?);
+            }
+        }
+
+        Ok(Self {
+            width: width.context("empty grid input")?,
+            height,
+            elements,
+        })
+    }
+}
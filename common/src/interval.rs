@@ -0,0 +1,500 @@
+use std::{
+    hash::{Hash, Hasher},
+    ops::Range,
+    sync::OnceLock,
+};
+
+use crate::math::Integer;
+
+fn length<T: Integer>(range: &Range<T>) -> T {
+    if range.start < range.end {
+        range.end - range.start
+    } else {
+        T::ZERO
+    }
+}
+
+// A set of integers stored as sorted, disjoint half-open ranges. Overlapping
+// and adjacent ranges are always merged.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    // Builds a set from ranges sorted by start, merging as it goes.
+    fn from_sorted(ranges: impl IntoIterator<Item = Range<T>>) -> Self {
+        let mut result = Vec::<Range<T>>::new();
+        for range in ranges {
+            if range.start >= range.end {
+                continue;
+            }
+            match result.last_mut() {
+                Some(last) if range.start <= last.end => {
+                    last.end = last.end.max(range.end);
+                }
+                _ => result.push(range),
+            }
+        }
+        Self { ranges: result }
+    }
+
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    // The number of integers in the set.
+    pub fn total_length(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::ZERO, |total, range| total + length(range))
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|range| range.start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|range| range.end - T::ONE)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|range| range.end <= value);
+        i < self.ranges.len() && self.ranges[i].start <= value
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.start >= range.end {
+            return;
+        }
+
+        let i = self.ranges.partition_point(|r| r.end < range.start);
+        let j = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = if i < j {
+            self.ranges[i].start.min(range.start)
+                ..self.ranges[j - 1].end.max(range.end)
+        } else {
+            range
+        };
+        self.ranges.splice(i..j, [merged]);
+    }
+
+    pub fn remove(&mut self, range: Range<T>) {
+        *self = self.difference(&range.into());
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut a = self.ranges.iter().peekable();
+        let mut b = other.ranges.iter().peekable();
+        Self::from_sorted(std::iter::from_fn(|| {
+            match (a.peek(), b.peek()) {
+                (Some(x), Some(y)) if x.start <= y.start => a.next(),
+                (Some(_), Some(_)) => b.next(),
+                (Some(_), None) => a.next(),
+                (None, _) => b.next(),
+            }
+            .cloned()
+        }))
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut result = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                result.push(start..end);
+            }
+            if a.end <= b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges: result }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = Vec::new();
+        let mut j = 0;
+        for range in self.ranges.iter() {
+            let mut start = range.start;
+            while j < other.ranges.len() && other.ranges[j].end <= start {
+                j += 1;
+            }
+            let mut k = j;
+            while start < range.end
+                && k < other.ranges.len()
+                && other.ranges[k].start < range.end
+            {
+                let cut = &other.ranges[k];
+                if start < cut.start {
+                    result.push(start..cut.start);
+                }
+                start = start.max(cut.end);
+                k += 1;
+            }
+            if start < range.end {
+                result.push(start..range.end);
+            }
+        }
+        Self { ranges: result }
+    }
+}
+
+impl<T: Integer> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Integer> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> Self {
+        Self::from_sorted([range])
+    }
+}
+
+impl<T: Integer> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut ranges = iter.into_iter().collect::<Vec<_>>();
+        ranges.sort_by_key(|range| range.start);
+        Self::from_sorted(ranges)
+    }
+}
+
+// A piecewise map from integers to integers. Each piece shifts a source range
+// so that it starts at a new destination, and every value outside of the
+// pieces maps to itself.
+#[derive(Clone, Debug)]
+pub struct IntervalMap<T> {
+    // Sorted and disjoint `(source, destination start)` pairs
+    pieces: Vec<(Range<T>, T)>,
+    // Built from the pieces on the first call to `min_over`, and thrown away
+    // whenever they change
+    index: OnceLock<MinIndex<T>>,
+}
+
+#[derive(Clone, Debug)]
+struct MinIndex<T> {
+    // The pieces along with the identity gaps between them, so that they
+    // cover everything from the first piece to the last without holes
+    cover: Vec<(Range<T>, T)>,
+    // A sparse table where `minima[k][i]` is the smallest destination start
+    // among `cover[i..i + 2^k]`
+    minima: Vec<Vec<T>>,
+}
+
+impl<T: Integer> MinIndex<T> {
+    fn new(pieces: &[(Range<T>, T)]) -> Self {
+        let cover = match (pieces.first(), pieces.last()) {
+            (Some(first), Some(last)) => {
+                IntervalMap::segments_of(pieces, first.0.start..last.0.end)
+            }
+            _ => Vec::new(),
+        };
+
+        let mut minima =
+            vec![cover.iter().map(|&(_, d)| d).collect::<Vec<_>>()];
+        let mut width = 1;
+        while 2 * width <= cover.len() {
+            let prev = minima.last().unwrap();
+            let next = (0..=cover.len() - 2 * width)
+                .map(|i| prev[i].min(prev[i + width]))
+                .collect();
+            minima.push(next);
+            width *= 2;
+        }
+
+        Self { cover, minima }
+    }
+}
+
+impl<T: Integer> IntervalMap<T> {
+    // The identity map.
+    pub fn new() -> Self {
+        Self::from_pieces(Vec::new())
+    }
+
+    // Requires that the pieces are sorted and disjoint.
+    fn from_pieces(pieces: Vec<(Range<T>, T)>) -> Self {
+        Self {
+            pieces,
+            index: OnceLock::new(),
+        }
+    }
+
+    pub fn pieces(&self) -> &[(Range<T>, T)] {
+        &self.pieces
+    }
+
+    // The values which are moved by a piece.
+    pub fn domain(&self) -> IntervalSet<T> {
+        IntervalSet::from_sorted(self.pieces.iter().map(|(r, _)| r.clone()))
+    }
+
+    // Maps `source` onto the range starting at `dest`. Values which are
+    // already covered by an earlier piece keep their existing mapping.
+    pub fn insert(&mut self, source: Range<T>, dest: T) {
+        if source.start >= source.end {
+            return;
+        }
+
+        // Only the pieces overlapping `source` can change
+        let i = self.pieces.partition_point(|(r, _)| r.end <= source.start);
+        let j = self.pieces.partition_point(|(r, _)| r.start < source.end);
+        let covered = IntervalSet::from_sorted(
+            self.pieces[i..j].iter().map(|(r, _)| r.clone()),
+        );
+
+        let uncovered = IntervalSet::from(source.clone()).difference(&covered);
+        let mut pieces = self.pieces[i..j].to_vec();
+        for range in uncovered.ranges {
+            let shifted = dest + (range.start - source.start);
+            pieces.push((range, shifted));
+        }
+        pieces.sort_by_key(|(range, _)| range.start);
+        self.pieces.splice(i..j, pieces);
+        self.index.take();
+    }
+
+    pub fn get(&self, value: T) -> T {
+        let i = self.pieces.partition_point(|(r, _)| r.end <= value);
+        match self.pieces.get(i) {
+            Some((range, dest)) if range.start <= value => {
+                *dest + (value - range.start)
+            }
+            _ => value,
+        }
+    }
+
+    // Splits `range` into consecutive pieces which are each shifted by a
+    // single offset, returned as `(source, destination start)` pairs.
+    pub fn segments(&self, range: Range<T>) -> Vec<(Range<T>, T)> {
+        Self::segments_of(&self.pieces, range)
+    }
+
+    fn segments_of(
+        pieces: &[(Range<T>, T)],
+        range: Range<T>,
+    ) -> Vec<(Range<T>, T)> {
+        let mut result = Vec::new();
+        let mut i = pieces.partition_point(|(r, _)| r.end <= range.start);
+        let mut cursor = range.start;
+        while cursor < range.end {
+            match pieces.get(i) {
+                Some((piece, dest)) if piece.start <= cursor => {
+                    let end = piece.end.min(range.end);
+                    result.push((cursor..end, *dest + (cursor - piece.start)));
+                    cursor = end;
+                    i += 1;
+                }
+                next => {
+                    let end = next
+                        .map_or(range.end, |(piece, _)| piece.start)
+                        .min(range.end);
+                    result.push((cursor..end, cursor));
+                    cursor = end;
+                }
+            }
+        }
+        result
+    }
+
+    // The image of `range` under the map.
+    pub fn map_range(&self, range: Range<T>) -> IntervalSet<T> {
+        self.segments(range)
+            .into_iter()
+            .map(|(source, dest)| dest..dest + length(&source))
+            .collect()
+    }
+
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        set.ranges
+            .iter()
+            .flat_map(|range| self.segments(range.clone()))
+            .map(|(source, dest)| dest..dest + length(&source))
+            .collect()
+    }
+
+    // The smallest value that anything in `range` maps to, found in
+    // logarithmic time.
+    pub fn min_over(&self, range: Range<T>) -> Option<T> {
+        if range.start >= range.end {
+            return None;
+        }
+
+        let MinIndex { cover, minima } =
+            self.index.get_or_init(|| MinIndex::new(&self.pieces));
+        let (Some(first), Some(last)) = (cover.first(), cover.last()) else {
+            return Some(range.start);
+        };
+        let (low, high) = (first.0.start, last.0.end);
+
+        let mut best = None;
+        let mut consider = |x: T| best = Some(best.map_or(x, |b: T| b.min(x)));
+
+        // Values outside of the cover map to themselves
+        if range.start < low {
+            consider(range.start);
+        }
+        if range.end > high {
+            consider(range.start.max(high));
+        }
+
+        let start = range.start.max(low);
+        let end = range.end.min(high);
+        if start < end {
+            let i = cover.partition_point(|(r, _)| r.end <= start);
+            let j = cover.partition_point(|(r, _)| r.start < end);
+
+            // Each segment is increasing, so only the first one can have a
+            // smaller value than its start
+            let (segment, dest) = &cover[i];
+            consider(*dest + (start - segment.start));
+            if i + 1 < j {
+                let level = (j - i - 1).ilog2() as usize;
+                let minima = &minima[level];
+                consider(minima[i + 1].min(minima[j - (1 << level)]));
+            }
+        }
+
+        best
+    }
+
+    // Returns the map which applies `self` and then `next`.
+    pub fn then(&self, next: &Self) -> Self {
+        let mut pieces = Vec::new();
+
+        for (source, dest) in self.pieces.iter() {
+            let image = *dest..*dest + length(source);
+            for (range, shifted) in next.segments(image) {
+                let start = source.start + (range.start - *dest);
+                pieces.push((start..start + length(&range), shifted));
+            }
+        }
+
+        // Values outside of this map's pieces pass straight through to `next`
+        let domain = self.domain();
+        for (source, dest) in next.pieces.iter() {
+            let uncovered =
+                IntervalSet::from(source.clone()).difference(&domain);
+            for range in uncovered.ranges {
+                let shifted = *dest + (range.start - source.start);
+                pieces.push((range, shifted));
+            }
+        }
+
+        pieces.sort_by_key(|(range, _)| range.start);
+        Self::normalized(pieces)
+    }
+
+    // Drops pieces which map to themselves and merges neighboring pieces
+    // with the same offset.
+    fn normalized(pieces: Vec<(Range<T>, T)>) -> Self {
+        let mut result = Vec::<(Range<T>, T)>::new();
+        for (range, dest) in pieces {
+            if range.start >= range.end || range.start == dest {
+                continue;
+            }
+            match result.last_mut() {
+                Some((last, last_dest))
+                    if last.end == range.start
+                        && *last_dest + length(last) == dest =>
+                {
+                    last.end = range.end;
+                }
+                _ => result.push((range, dest)),
+            }
+        }
+        Self::from_pieces(result)
+    }
+}
+
+// Maps are compared by their pieces alone, whether or not the index has been
+// built
+impl<T: PartialEq> PartialEq for IntervalMap<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.pieces == other.pieces
+    }
+}
+
+impl<T: Eq> Eq for IntervalMap<T> {}
+
+impl<T: Hash> Hash for IntervalMap<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.pieces.hash(state);
+    }
+}
+
+impl<T: Integer> Default for IntervalMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// An axis-aligned box of integer points: the product of a half-open range on
+// each of `N` axes.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Hyperrect<T, const N: usize> {
+    ranges: [Range<T>; N],
+}
+
+impl<T: Integer, const N: usize> Hyperrect<T, N> {
+    pub fn new(ranges: [Range<T>; N]) -> Self {
+        Self { ranges }
+    }
+
+    pub fn ranges(&self) -> &[Range<T>; N] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.iter().any(|range| range.start >= range.end)
+    }
+
+    // The number of points in the box.
+    pub fn volume(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::ONE, |volume, range| volume * length(range))
+    }
+
+    pub fn contains(&self, point: &[T; N]) -> bool {
+        self.ranges
+            .iter()
+            .zip(point)
+            .all(|(range, x)| range.contains(x))
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let mut ranges = self.ranges.clone();
+        for (range, other) in ranges.iter_mut().zip(other.ranges.iter()) {
+            range.start = range.start.max(other.start);
+            range.end = range.end.min(other.end);
+        }
+        let result = Self { ranges };
+        (!result.is_empty()).then_some(result)
+    }
+
+    // Splits the box along `axis` into the points below `at` and the points
+    // at or above it. Either half may be empty.
+    pub fn split(&self, axis: usize, at: T) -> (Self, Self) {
+        let range = &self.ranges[axis];
+        let at = at.clamp(range.start, range.end.max(range.start));
+        let mut below = self.clone();
+        let mut above = self.clone();
+        below.ranges[axis] = range.start..at;
+        above.ranges[axis] = at..range.end;
+        (below, above)
+    }
+}
@@ -0,0 +1,152 @@
+use std::ops::{Index, IndexMut};
+
+use crate::num::Field;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    elements: Vec<T>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Solution<T> {
+    Unique(Vec<T>),
+    // The system has infinitely many solutions because its rank is less than
+    // the number of unknowns.
+    Underdetermined { rank: usize },
+    Inconsistent,
+}
+
+impl<T> Matrix<T> {
+    // Elements are given in row-major order.
+    pub fn from_elements(rows: usize, cols: usize, elements: Vec<T>) -> Self {
+        assert_eq!(rows * cols, elements.len());
+
+        Self {
+            rows,
+            cols,
+            elements,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.elements[row * self.cols..(row + 1) * self.cols]
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        if a != b {
+            for col in 0..self.cols {
+                self.elements.swap(a * self.cols + col, b * self.cols + col);
+            }
+        }
+    }
+}
+
+impl<T: Field> Matrix<T> {
+    pub fn zero(rows: usize, cols: usize) -> Self {
+        Self::from_elements(rows, cols, vec![T::zero(); rows * cols])
+    }
+
+    pub fn identity(size: usize) -> Self {
+        let mut result = Self::zero(size, size);
+        for i in 0..size {
+            result[(i, i)] = T::one();
+        }
+        result
+    }
+
+    // Transforms the matrix into reduced row echelon form using exact
+    // Gauss-Jordan elimination, returning the pivot column of each nonzero
+    // row.
+    pub fn row_reduce(&mut self) -> Vec<usize> {
+        let mut pivots = Vec::new();
+
+        for col in 0..self.cols {
+            let row = pivots.len();
+            let Some(pivot) =
+                (row..self.rows).find(|&r| !self[(r, col)].is_zero())
+            else {
+                continue;
+            };
+            self.swap_rows(row, pivot);
+
+            let scale = T::one() / self[(row, col)].clone();
+            for c in col..self.cols {
+                self[(row, c)] = self[(row, c)].clone() * scale.clone();
+            }
+
+            for r in 0..self.rows {
+                if r != row && !self[(r, col)].is_zero() {
+                    let factor = self[(r, col)].clone();
+                    for c in col..self.cols {
+                        self[(r, c)] = self[(r, c)].clone()
+                            - factor.clone() * self[(row, c)].clone();
+                    }
+                }
+            }
+
+            pivots.push(col);
+            if pivots.len() == self.rows {
+                break;
+            }
+        }
+
+        pivots
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().row_reduce().len()
+    }
+
+    // Solves `self * x = rhs` for `x`.
+    pub fn solve(&self, rhs: &[T]) -> Solution<T> {
+        assert_eq!(self.rows, rhs.len());
+
+        let cols = self.cols + 1;
+        let mut augmented = Matrix::zero(self.rows, cols);
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                augmented[(r, c)] = self[(r, c)].clone();
+            }
+            augmented[(r, self.cols)] = rhs[r].clone();
+        }
+
+        let pivots = augmented.row_reduce();
+        if pivots.last() == Some(&self.cols) {
+            Solution::Inconsistent
+        } else if pivots.len() < self.cols {
+            Solution::Underdetermined { rank: pivots.len() }
+        } else {
+            Solution::Unique(
+                (0..self.cols)
+                    .map(|r| augmented[(r, self.cols)].clone())
+                    .collect(),
+            )
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        assert!(row < self.rows && col < self.cols);
+        &self.elements[row * self.cols + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        assert!(row < self.rows && col < self.cols);
+        &mut self.elements[row * self.cols + col]
+    }
+}
@@ -0,0 +1,210 @@
+use std::{
+    fmt::Debug,
+    hash::Hash,
+    ops::{Add, Div, Mul, Neg, Rem, Sub},
+};
+
+pub trait Integer:
+    Copy
+    + Debug
+    + Ord
+    + Hash
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs(self) -> Self;
+    // The absolute value, or `None` if it does not fit in `Self`.
+    fn checked_abs(self) -> Option<Self>;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+}
+
+pub trait Signed: Integer + Neg<Output = Self> {}
+
+macro_rules! impl_integer {
+    ($abs:ident: $($ty:ty),*) => {
+        $(
+            impl Integer for $ty {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn abs(self) -> Self {
+                    impl_integer!(@$abs self)
+                }
+
+                fn checked_abs(self) -> Option<Self> {
+                    impl_integer!(@checked $abs self)
+                }
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$ty>::checked_add(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$ty>::checked_mul(self, rhs)
+                }
+            }
+        )*
+    };
+    (@signed $value:ident) => { $value.abs() };
+    (@unsigned $value:ident) => { $value };
+    (@checked signed $value:ident) => { $value.checked_abs() };
+    (@checked unsigned $value:ident) => { Some($value) };
+}
+
+impl_integer!(unsigned: u8, u16, u32, u64, u128, usize);
+impl_integer!(signed: i8, i16, i32, i64, i128, isize);
+
+impl Signed for i8 {}
+impl Signed for i16 {}
+impl Signed for i32 {}
+impl Signed for i64 {}
+impl Signed for i128 {}
+impl Signed for isize {}
+
+// The greatest common divisor of `a` and `b`, or `None` if it does not fit
+// in `T`. Always non-negative.
+pub fn checked_gcd<T: Integer>(a: T, b: T) -> Option<T> {
+    // The absolute value of `T::MIN` does not fit, but its remainder by any
+    // other nonzero value does
+    let (mut a, mut b) = match (a.checked_abs(), b.checked_abs()) {
+        (Some(a), Some(b)) => (a, b),
+        (None, Some(b)) if b != T::ZERO => (b, (a % b).checked_abs()?),
+        (Some(a), None) if a != T::ZERO => (a, (b % a).checked_abs()?),
+        _ => return None,
+    };
+    while b != T::ZERO {
+        let t = b;
+        b = a % b;
+        a = t;
+    }
+    Some(a)
+}
+
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    checked_gcd(a, b).expect("greatest common divisor overflowed")
+}
+
+// The least common multiple of `a` and `b`, or `None` if it does not fit in
+// `T`. Always non-negative.
+pub fn checked_lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    let (a, b) = (a.checked_abs()?, b.checked_abs()?);
+    if a == T::ZERO || b == T::ZERO {
+        Some(T::ZERO)
+    } else {
+        (a / gcd(a, b)).checked_mul(b)
+    }
+}
+
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    checked_lcm(a, b).expect("least common multiple overflowed")
+}
+
+// Returns `(g, x, y)` such that `a * x + b * y = g`, where `g` is the
+// greatest common divisor of `a` and `b`.
+pub fn extended_gcd<T: Signed>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (T::ONE, T::ZERO);
+    let (mut old_t, mut t) = (T::ZERO, T::ONE);
+
+    while r != T::ZERO {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+        (old_t, t) = (t, old_t - q * t);
+    }
+
+    if old_r < T::ZERO {
+        (-old_r, -old_s, -old_t)
+    } else {
+        (old_r, old_s, old_t)
+    }
+}
+
+// Reduces `a` into the range `0..m`.
+pub fn rem_euclid<T: Integer>(a: T, m: T) -> T {
+    let r = a % m;
+    if r < T::ZERO {
+        r + m.abs()
+    } else {
+        r
+    }
+}
+
+// Returns `x` in `0..m` such that `a * x` is congruent to 1 modulo `m`, if
+// one exists.
+pub fn mod_inverse<T: Signed>(a: T, m: T) -> Option<T> {
+    let (g, x, _) = extended_gcd(rem_euclid(a, m), m);
+    (g == T::ONE).then(|| rem_euclid(x, m))
+}
+
+// Computes `a * b` modulo `m` for `a` and `b` in `0..m` without overflowing,
+// even when the product would not fit in `T`.
+pub fn mul_mod<T: Integer>(a: T, b: T, m: T) -> T {
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+
+    let two = T::ONE + T::ONE;
+    let add_mod = |x: T, y: T| if x >= m - y { x - (m - y) } else { x + y };
+
+    let mut result = T::ZERO;
+    let mut a = a;
+    let mut b = b;
+    while b != T::ZERO {
+        if b % two == T::ONE {
+            result = add_mod(result, a);
+        }
+        a = add_mod(a, a);
+        b = b / two;
+    }
+    result
+}
+
+// Solves a system of congruences `x = r (mod m)` given as `(r, m)` pairs. The
+// moduli need not be coprime. Returns `(x, m)` where `x` is the smallest
+// non-negative solution and `m` is the least common multiple of the moduli,
+// so every solution is congruent to `x` modulo `m`. Returns `None` if the
+// system has no solution, a modulus is zero, or the combined modulus does not
+// fit in `T`.
+pub fn crt<T: Signed>(
+    congruences: impl IntoIterator<Item = (T, T)>,
+) -> Option<(T, T)> {
+    let mut x = T::ZERO;
+    let mut m = T::ONE;
+
+    for (r, n) in congruences {
+        let n = n.checked_abs()?;
+        if n == T::ZERO {
+            return None;
+        }
+        let r = rem_euclid(r, n);
+
+        // Find k such that x + m * k = r (mod n)
+        let g = gcd(m, n);
+        let diff = rem_euclid(r - x, n);
+        if diff % g != T::ZERO {
+            return None;
+        }
+
+        let n_g = n / g;
+        let k = if n_g == T::ONE {
+            T::ZERO
+        } else {
+            let inverse = mod_inverse(m / g % n_g, n_g)?;
+            mul_mod(diff / g % n_g, inverse, n_g)
+        };
+
+        let new_m = (m / g).checked_mul(n)?;
+        x = rem_euclid(x.checked_add(m.checked_mul(k)?)?, new_m);
+        m = new_m;
+    }
+
+    Some((x, m))
+}
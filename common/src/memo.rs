@@ -0,0 +1,141 @@
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, Hash},
+    rc::Rc,
+};
+
+// Somewhere to keep the results of a memoized function.
+pub trait Store<K, V> {
+    fn get(&self, key: &K) -> Option<&V>;
+    fn insert(&mut self, key: K, value: V);
+}
+
+impl<K: Hash + Eq, V, H: BuildHasher> Store<K, V> for HashMap<K, V, H> {
+    fn get(&self, key: &K) -> Option<&V> {
+        HashMap::get(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        HashMap::insert(self, key, value);
+    }
+}
+
+// A key made of one or more indices, each less than the corresponding bound.
+pub trait DenseKey {
+    // The number of keys less than `bounds`.
+    fn size(bounds: &Self) -> usize;
+    // Flattens the key into `0..size(bounds)` in row-major order.
+    fn flatten(&self, bounds: &Self) -> usize;
+}
+
+impl DenseKey for usize {
+    fn size(bounds: &Self) -> usize {
+        *bounds
+    }
+
+    fn flatten(&self, bounds: &Self) -> usize {
+        assert!(self < bounds, "key {self} out of bounds {bounds}");
+        *self
+    }
+}
+
+macro_rules! impl_dense_key {
+    ($($index:tt),*) => {
+        impl DenseKey for ($(impl_dense_key!(@usize $index),)*) {
+            fn size(bounds: &Self) -> usize {
+                1 $(* bounds.$index)*
+            }
+
+            fn flatten(&self, bounds: &Self) -> usize {
+                assert!(
+                    true $(&& self.$index < bounds.$index)*,
+                    "key {self:?} out of bounds {bounds:?}",
+                );
+                let mut index = 0;
+                $(index = index * bounds.$index + self.$index;)*
+                index
+            }
+        }
+    };
+    (@usize $index:tt) => { usize };
+}
+
+impl_dense_key!(0, 1);
+impl_dense_key!(0, 1, 2);
+impl_dense_key!(0, 1, 2, 3);
+
+// A flat array of results for keys with known bounds. Much faster than
+// hashing when the key space is small and densely used.
+pub struct Dense<K, V> {
+    bounds: K,
+    values: Vec<Option<V>>,
+}
+
+impl<K: DenseKey, V> Dense<K, V> {
+    pub fn new(bounds: K) -> Self {
+        let mut values = Vec::new();
+        values.resize_with(K::size(&bounds), || None);
+        Self { bounds, values }
+    }
+}
+
+impl<K: DenseKey, V> Store<K, V> for Dense<K, V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        self.values[key.flatten(&self.bounds)].as_ref()
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        let index = key.flatten(&self.bounds);
+        self.values[index] = Some(value);
+    }
+}
+
+// A memoized recursive function. The function receives the memo so that it
+// can call itself through `get_or_compute`.
+pub struct Memo<'f, K, V, S = HashMap<K, V>> {
+    store: S,
+    f: Rc<Recurse<'f, K, V, S>>,
+}
+
+type Recurse<'f, K, V, S> = dyn Fn(&mut Memo<'f, K, V, S>, K) -> V + 'f;
+
+impl<'f, K: Hash + Eq, V> Memo<'f, K, V> {
+    pub fn new(f: impl Fn(&mut Self, K) -> V + 'f) -> Self {
+        Self::with_store(HashMap::new(), f)
+    }
+}
+
+impl<'f, K: DenseKey, V> Memo<'f, K, V, Dense<K, V>> {
+    // Memoizes into a flat array. Every key must be less than `bounds`.
+    pub fn dense(bounds: K, f: impl Fn(&mut Self, K) -> V + 'f) -> Self {
+        Self::with_store(Dense::new(bounds), f)
+    }
+}
+
+impl<'f, K, V, S: Store<K, V>> Memo<'f, K, V, S> {
+    pub fn with_store(store: S, f: impl Fn(&mut Self, K) -> V + 'f) -> Self {
+        Self {
+            store,
+            f: Rc::new(f),
+        }
+    }
+
+    pub fn get_or_compute(&mut self, key: K) -> V
+    where
+        K: Clone,
+        V: Clone,
+    {
+        if let Some(value) = self.store.get(&key) {
+            return value.clone();
+        }
+
+        let f = Rc::clone(&self.f);
+        let value = f(self, key.clone());
+        self.store.insert(key, value.clone());
+        value
+    }
+
+    pub fn into_store(self) -> S {
+        self.store
+    }
+}
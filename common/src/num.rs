@@ -0,0 +1,97 @@
+mod big_int;
+mod rational;
+
+use std::{
+    fmt::{Debug, Display},
+    ops::{Add, Div, Mul, Neg, Rem, Sub},
+};
+
+pub use self::{big_int::BigInt, rational::Rational};
+
+pub type BigRational = Rational<BigInt>;
+
+// A number supporting exact addition, subtraction and multiplication.
+pub trait Ring:
+    Clone
+    + Debug
+    + Display
+    + PartialEq
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn from_i64(value: i64) -> Self;
+
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+}
+
+// A ring which also supports division by any nonzero value.
+pub trait Field: Ring + Div<Output = Self> {}
+
+// An integer type which rationals can be built from. Division truncates
+// toward zero.
+pub trait Int: Ring + Ord + Div<Output = Self> + Rem<Output = Self> {
+    fn checked_add(&self, rhs: &Self) -> Option<Self>;
+    fn checked_sub(&self, rhs: &Self) -> Option<Self>;
+    fn checked_mul(&self, rhs: &Self) -> Option<Self>;
+
+    fn abs(&self) -> Self {
+        if *self < Self::zero() {
+            -self.clone()
+        } else {
+            self.clone()
+        }
+    }
+
+    fn gcd(&self, other: &Self) -> Self {
+        let mut a = self.abs();
+        let mut b = other.abs();
+        while !b.is_zero() {
+            let t = a % b.clone();
+            a = b;
+            b = t;
+        }
+        a
+    }
+}
+
+macro_rules! impl_primitive {
+    ($($ty:ty),*) => {
+        $(
+            impl Ring for $ty {
+                fn zero() -> Self {
+                    0
+                }
+
+                fn one() -> Self {
+                    1
+                }
+
+                fn from_i64(value: i64) -> Self {
+                    value.try_into().expect("value out of range")
+                }
+            }
+
+            impl Int for $ty {
+                fn checked_add(&self, rhs: &Self) -> Option<Self> {
+                    <$ty>::checked_add(*self, *rhs)
+                }
+
+                fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+                    <$ty>::checked_sub(*self, *rhs)
+                }
+
+                fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+                    <$ty>::checked_mul(*self, *rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive!(i32, i64, i128, isize);
@@ -0,0 +1,389 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, Div, Mul, Neg, Rem, Sub},
+    str::FromStr,
+};
+
+use super::{Int, Ring};
+use crate::{bail, Error};
+
+// An arbitrary-precision integer. The magnitude is stored as little-endian
+// 32-bit limbs with no trailing zero limbs, and zero is never negative.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: Vec<u32>,
+}
+
+impl BigInt {
+    fn from_parts(negative: bool, mut magnitude: Vec<u32>) -> Self {
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+        Self {
+            negative: negative && !magnitude.is_empty(),
+            magnitude,
+        }
+    }
+
+    fn from_u128(negative: bool, mut value: u128) -> Self {
+        let mut magnitude = Vec::new();
+        while value != 0 {
+            magnitude.push(value as u32);
+            value >>= 32;
+        }
+        Self::from_parts(negative, magnitude)
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn to_i128(&self) -> Option<i128> {
+        if self.magnitude.len() > 4 {
+            return None;
+        }
+
+        let value = self
+            .magnitude
+            .iter()
+            .rev()
+            .fold(0u128, |v, &limb| v << 32 | limb as u128);
+        if self.negative {
+            0i128.checked_sub_unsigned(value)
+        } else {
+            value.try_into().ok()
+        }
+    }
+
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut base = self.clone();
+        let mut result = Self::one();
+        while exp != 0 {
+            if exp & 1 != 0 {
+                result = &result * &base;
+            }
+            base = &base * &base;
+            exp >>= 1;
+        }
+        result
+    }
+
+    // Returns the truncated quotient and the remainder, which has the same
+    // sign as `self`.
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        assert!(!rhs.magnitude.is_empty(), "attempt to divide by zero");
+        let (q, r) = div_rem_magnitude(&self.magnitude, &rhs.magnitude);
+        (
+            Self::from_parts(self.negative != rhs.negative, q),
+            Self::from_parts(self.negative, r),
+        )
+    }
+}
+
+fn cmp_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut result = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0u64;
+    for (i, &limb) in a.iter().enumerate() {
+        let sum = limb as u64 + b.get(i).copied().unwrap_or(0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry != 0 {
+        result.push(carry as u32);
+    }
+    result
+}
+
+// Requires that `a >= b`.
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &limb) in a.iter().enumerate() {
+        let mut diff =
+            limb as i64 - b.get(i).copied().unwrap_or(0) as i64 - borrow;
+        borrow = 0;
+        if diff < 0 {
+            diff += 1 << 32;
+            borrow = 1;
+        }
+        result.push(diff as u32);
+    }
+    debug_assert_eq!(borrow, 0);
+    result
+}
+
+fn mul_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            let product = x as u64 * y as u64 + result[i + j] as u64 + carry;
+            result[i + j] = product as u32;
+            carry = product >> 32;
+        }
+        result[i + b.len()] = carry as u32;
+    }
+    result
+}
+
+fn div_rem_small(a: &[u32], d: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0u32; a.len()];
+    let mut rem = 0u64;
+    for (i, &limb) in a.iter().enumerate().rev() {
+        let cur = rem << 32 | limb as u64;
+        quotient[i] = (cur / d as u64) as u32;
+        rem = cur % d as u64;
+    }
+    (quotient, rem as u32)
+}
+
+fn div_rem_magnitude(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if cmp_magnitude(a, b) == Ordering::Less {
+        return (Vec::new(), a.to_vec());
+    }
+    if b.len() == 1 {
+        let (q, r) = div_rem_small(a, b[0]);
+        return (q, vec![r]);
+    }
+
+    // Binary long division
+    let mut quotient = vec![0u32; a.len()];
+    let mut rem: Vec<u32> = Vec::with_capacity(b.len() + 1);
+    for bit in (0..a.len() * 32).rev() {
+        // rem = rem << 1 | bit
+        let mut carry = a[bit / 32] >> (bit % 32) & 1;
+        for limb in rem.iter_mut() {
+            let next = *limb >> 31;
+            *limb = *limb << 1 | carry;
+            carry = next;
+        }
+        if carry != 0 {
+            rem.push(carry);
+        }
+
+        if cmp_magnitude(&rem, b) != Ordering::Less {
+            rem = sub_magnitude(&rem, b);
+            while rem.last() == Some(&0) {
+                rem.pop();
+            }
+            quotient[bit / 32] |= 1 << (bit % 32);
+        }
+    }
+    (quotient, rem)
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        Self::from_u128(value < 0, value.unsigned_abs() as u128)
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(value: i128) -> Self {
+        Self::from_u128(value < 0, value.unsigned_abs())
+    }
+}
+
+impl From<u64> for BigInt {
+    fn from(value: u64) -> Self {
+        Self::from_u128(false, value as u128)
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            bail!("invalid integer '{s}'");
+        }
+
+        let mut magnitude = Vec::new();
+        for chunk in digits.as_bytes().chunks(9) {
+            let scale = 10u32.pow(chunk.len() as u32);
+            let value =
+                chunk.iter().fold(0u32, |v, &b| v * 10 + (b - b'0') as u32);
+            magnitude = mul_magnitude(&magnitude, &[scale]);
+            magnitude = add_magnitude(&magnitude, &[value]);
+        }
+
+        Ok(Self::from_parts(negative, magnitude))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.magnitude.is_empty() {
+            return write!(f, "0");
+        }
+
+        let mut chunks = Vec::new();
+        let mut magnitude = self.magnitude.clone();
+        while !magnitude.is_empty() {
+            let (q, r) = div_rem_small(&magnitude, 1_000_000_000);
+            chunks.push(r);
+            magnitude = q;
+            while magnitude.last() == Some(&0) {
+                magnitude.pop();
+            }
+        }
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", chunks.last().unwrap())?;
+        for chunk in chunks.iter().rev().skip(1) {
+            write!(f, "{chunk:09}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitude(&self.magnitude, &other.magnitude),
+            (true, true) => cmp_magnitude(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        if self.negative == rhs.negative {
+            BigInt::from_parts(
+                self.negative,
+                add_magnitude(&self.magnitude, &rhs.magnitude),
+            )
+        } else {
+            match cmp_magnitude(&self.magnitude, &rhs.magnitude) {
+                Ordering::Less => BigInt::from_parts(
+                    rhs.negative,
+                    sub_magnitude(&rhs.magnitude, &self.magnitude),
+                ),
+                _ => BigInt::from_parts(
+                    self.negative,
+                    sub_magnitude(&self.magnitude, &rhs.magnitude),
+                ),
+            }
+        }
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> Self::Output {
+        BigInt::from_parts(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> Self::Output {
+        BigInt::from_parts(!self.negative, self.magnitude)
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + &-rhs
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        BigInt::from_parts(
+            self.negative != rhs.negative,
+            mul_magnitude(&self.magnitude, &rhs.magnitude),
+        )
+    }
+}
+
+impl Div for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.div_rem(rhs).1
+    }
+}
+
+macro_rules! forward_owned {
+    ($($trait:ident::$method:ident),*) => {
+        $(
+            impl $trait for BigInt {
+                type Output = BigInt;
+
+                fn $method(self, rhs: Self) -> Self::Output {
+                    (&self).$method(&rhs)
+                }
+            }
+        )*
+    };
+}
+
+forward_owned!(Add::add, Sub::sub, Mul::mul, Div::div, Rem::rem);
+
+impl Ring for BigInt {
+    fn zero() -> Self {
+        Self::default()
+    }
+
+    fn one() -> Self {
+        Self::from(1i64)
+    }
+
+    fn from_i64(value: i64) -> Self {
+        Self::from(value)
+    }
+}
+
+impl Int for BigInt {
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Some(self + rhs)
+    }
+
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        Some(self - rhs)
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        Some(self * rhs)
+    }
+}
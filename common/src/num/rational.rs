@@ -0,0 +1,207 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+use super::{Field, Int, Ring};
+
+// An exact fraction, always kept in lowest terms with a positive denominator.
+// Operations panic if an intermediate value overflows `T`; the `checked_*`
+// methods return `None` instead.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Rational<T> {
+    numer: T,
+    denom: T,
+}
+
+impl<T: Int> Rational<T> {
+    pub fn new(numer: T, denom: T) -> Self {
+        assert!(!denom.is_zero(), "rational with zero denominator");
+        Self::reduce(numer, denom)
+    }
+
+    pub fn from_integer(value: T) -> Self {
+        Self {
+            numer: value,
+            denom: T::one(),
+        }
+    }
+
+    fn reduce(numer: T, denom: T) -> Self {
+        let g = numer.gcd(&denom);
+        let (numer, denom) = (numer / g.clone(), denom / g);
+        if denom < T::zero() {
+            Self {
+                numer: -numer,
+                denom: -denom,
+            }
+        } else {
+            Self { numer, denom }
+        }
+    }
+
+    pub fn numer(&self) -> &T {
+        &self.numer
+    }
+
+    pub fn denom(&self) -> &T {
+        &self.denom
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denom == T::one()
+    }
+
+    pub fn to_integer(&self) -> Option<T> {
+        self.is_integer().then(|| self.numer.clone())
+    }
+
+    // The largest integer less than or equal to this value.
+    pub fn floor(&self) -> T {
+        let q = self.numer.clone() / self.denom.clone();
+        if self.numer < T::zero()
+            && q.clone() * self.denom.clone() != self.numer
+        {
+            q - T::one()
+        } else {
+            q
+        }
+    }
+
+    pub fn recip(&self) -> Self {
+        Self::new(self.denom.clone(), self.numer.clone())
+    }
+
+    pub fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        let g = self.denom.gcd(&rhs.denom);
+        let l = self.numer.checked_mul(&(rhs.denom.clone() / g.clone()))?;
+        let r = rhs.numer.checked_mul(&(self.denom.clone() / g.clone()))?;
+        let denom = (self.denom.clone() / g).checked_mul(&rhs.denom)?;
+        Some(Self::reduce(l.checked_add(&r)?, denom))
+    }
+
+    pub fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        let g = self.denom.gcd(&rhs.denom);
+        let l = self.numer.checked_mul(&(rhs.denom.clone() / g.clone()))?;
+        let r = rhs.numer.checked_mul(&(self.denom.clone() / g.clone()))?;
+        let denom = (self.denom.clone() / g).checked_mul(&rhs.denom)?;
+        Some(Self::reduce(l.checked_sub(&r)?, denom))
+    }
+
+    pub fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        // Cancel common factors first so the result is already reduced
+        let g1 = self.numer.gcd(&rhs.denom);
+        let g2 = rhs.numer.gcd(&self.denom);
+        let numer = (self.numer.clone() / g1.clone())
+            .checked_mul(&(rhs.numer.clone() / g2.clone()))?;
+        let denom =
+            (self.denom.clone() / g2).checked_mul(&(rhs.denom.clone() / g1))?;
+        Some(Self { numer, denom })
+    }
+
+    // Returns `None` on overflow or division by zero.
+    pub fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        if rhs.numer.is_zero() {
+            None
+        } else {
+            self.checked_mul(&rhs.recip())
+        }
+    }
+}
+
+impl<T: Int> From<T> for Rational<T> {
+    fn from(value: T) -> Self {
+        Self::from_integer(value)
+    }
+}
+
+impl<T: Int> Add for Rational<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(&rhs)
+            .expect("rational addition overflowed")
+    }
+}
+
+impl<T: Int> Sub for Rational<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(&rhs)
+            .expect("rational subtraction overflowed")
+    }
+}
+
+impl<T: Int> Mul for Rational<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(&rhs)
+            .expect("rational multiplication overflowed")
+    }
+}
+
+impl<T: Int> Div for Rational<T> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        assert!(!rhs.numer.is_zero(), "rational division by zero");
+        self.checked_div(&rhs)
+            .expect("rational division overflowed")
+    }
+}
+
+impl<T: Int> Neg for Rational<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            numer: -self.numer,
+            denom: self.denom,
+        }
+    }
+}
+
+impl<T: Int> Ord for Rational<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let l = self.numer.checked_mul(&other.denom);
+        let r = other.numer.checked_mul(&self.denom);
+        l.zip(r)
+            .map(|(l, r)| l.cmp(&r))
+            .expect("rational comparison overflowed")
+    }
+}
+
+impl<T: Int> PartialOrd for Rational<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Int> fmt::Display for Rational<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+impl<T: Int> Ring for Rational<T> {
+    fn zero() -> Self {
+        Self::from_integer(T::zero())
+    }
+
+    fn one() -> Self {
+        Self::from_integer(T::one())
+    }
+
+    fn from_i64(value: i64) -> Self {
+        Self::from_integer(T::from_i64(value))
+    }
+}
+
+impl<T: Int> Field for Rational<T> {}
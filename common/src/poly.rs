@@ -0,0 +1,165 @@
+use std::ops::{Add, Mul};
+
+use crate::{
+    bail,
+    num::{Field, Ring},
+    Result,
+};
+
+// Repeatedly takes differences of neighboring values until they are all zero.
+// The first row is the values themselves. Stops early if the values run out
+// before reaching zero.
+pub fn differences<T: Ring>(values: &[T]) -> Vec<Vec<T>> {
+    let mut table = vec![values.to_vec()];
+    loop {
+        let last = table.last().unwrap();
+        if last.iter().all(T::is_zero) || last.len() < 2 {
+            break;
+        }
+        let next = last
+            .windows(2)
+            .map(|w| w[1].clone() - w[0].clone())
+            .collect();
+        table.push(next);
+    }
+    table
+}
+
+// Predicts the value after the last one by extending the difference table.
+pub fn extrapolate<T: Ring>(values: &[T]) -> T {
+    differences(values)
+        .iter()
+        .filter_map(|row| row.last().cloned())
+        .fold(T::zero(), |total, last| total + last)
+}
+
+// Predicts the value before the first one by extending the difference table.
+pub fn extrapolate_back<T: Ring>(values: &[T]) -> T {
+    differences(values)
+        .iter()
+        .rev()
+        .filter_map(|row| row.first().cloned())
+        .fold(T::zero(), |below, first| first - below)
+}
+
+// A polynomial in one variable, stored as coefficients from the constant term
+// upward with no trailing zeros.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polynomial<T> {
+    coefficients: Vec<T>,
+}
+
+impl<T: Ring> Polynomial<T> {
+    pub fn new(mut coefficients: Vec<T>) -> Self {
+        while coefficients.last().is_some_and(T::is_zero) {
+            coefficients.pop();
+        }
+        Self { coefficients }
+    }
+
+    pub fn zero() -> Self {
+        Self::new(Vec::new())
+    }
+
+    pub fn constant(value: T) -> Self {
+        Self::new(vec![value])
+    }
+
+    pub fn coefficients(&self) -> &[T] {
+        &self.coefficients
+    }
+
+    // The degree of the polynomial, or `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    pub fn evaluate(&self, x: &T) -> T {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(T::zero(), |total, c| total * x.clone() + c.clone())
+    }
+}
+
+impl<T: Field> Polynomial<T> {
+    // The unique polynomial of degree less than `points.len()` which passes
+    // through all of the points. Panics if two points share an x value.
+    pub fn interpolate(points: &[(T, T)]) -> Self {
+        let mut result = Self::zero();
+        for (i, (xi, yi)) in points.iter().enumerate() {
+            let mut basis = Self::constant(yi.clone());
+            for (j, (xj, _)) in points.iter().enumerate() {
+                if i != j {
+                    let denom = xi.clone() - xj.clone();
+                    assert!(!denom.is_zero(), "duplicate x value {xi}");
+                    let scale = T::one() / denom;
+                    basis = basis
+                        * Self::new(vec![-xj.clone() * scale.clone(), scale]);
+                }
+            }
+            result = result + basis;
+        }
+        result
+    }
+
+    // Fits a polynomial of at most `degree` to the samples. The first
+    // `degree + 1` samples determine it and any others must agree with it.
+    pub fn fit(samples: &[(T, T)], degree: usize) -> Result<Self> {
+        if samples.len() <= degree {
+            bail!(
+                "a degree {degree} fit needs {} samples but only {} were given",
+                degree + 1,
+                samples.len(),
+            );
+        }
+
+        let result = Self::interpolate(&samples[..=degree]);
+        for (x, y) in samples[degree + 1..].iter() {
+            let expected = result.evaluate(x);
+            if expected != *y {
+                bail!(
+                    "sample ({x}, {y}) does not fit a degree {degree} \
+                     polynomial, which predicts {expected}"
+                );
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl<T: Ring> Add for Polynomial<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let (mut long, short) =
+            if self.coefficients.len() >= rhs.coefficients.len() {
+                (self.coefficients, rhs.coefficients)
+            } else {
+                (rhs.coefficients, self.coefficients)
+            };
+        for (a, b) in long.iter_mut().zip(short) {
+            *a = a.clone() + b;
+        }
+        Self::new(long)
+    }
+}
+
+impl<T: Ring> Mul for Polynomial<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        if self.coefficients.is_empty() || rhs.coefficients.is_empty() {
+            return Self::zero();
+        }
+
+        let len = self.coefficients.len() + rhs.coefficients.len() - 1;
+        let mut result = vec![T::zero(); len];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in rhs.coefficients.iter().enumerate() {
+                result[i + j] = result[i + j].clone() + a.clone() * b.clone();
+            }
+        }
+        Self::new(result)
+    }
+}
@@ -0,0 +1,353 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+pub trait Cost: Copy + Ord + Default + Add<Output = Self> {}
+
+impl<T: Copy + Ord + Default + Add<Output = Self>> Cost for T {}
+
+// The result of a search. States which were discovered but not settled before
+// the search reached its goal carry tentative distances.
+pub struct Search<S, C> {
+    states: Vec<S>,
+    ids: HashMap<S, usize>,
+    costs: Vec<C>,
+    parents: Vec<Option<usize>>,
+    settled: Vec<bool>,
+    goal: Option<usize>,
+}
+
+impl<S: Clone + Eq + Hash, C: Cost> Search<S, C> {
+    fn new() -> Self {
+        Self {
+            states: Vec::new(),
+            ids: HashMap::new(),
+            costs: Vec::new(),
+            parents: Vec::new(),
+            settled: Vec::new(),
+            goal: None,
+        }
+    }
+
+    // Records a path to `state` with the given cost, returning the id of the
+    // state if the path is an improvement.
+    fn relax(
+        &mut self,
+        state: S,
+        cost: C,
+        parent: Option<usize>,
+    ) -> Option<usize> {
+        if let Some(&id) = self.ids.get(&state) {
+            if self.settled[id] || self.costs[id] <= cost {
+                return None;
+            }
+            self.costs[id] = cost;
+            self.parents[id] = parent;
+            Some(id)
+        } else {
+            let id = self.states.len();
+            self.ids.insert(state.clone(), id);
+            self.states.push(state);
+            self.costs.push(cost);
+            self.parents.push(parent);
+            self.settled.push(false);
+            Some(id)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    pub fn distance(&self, state: &S) -> Option<C> {
+        self.ids.get(state).map(|&id| self.costs[id])
+    }
+
+    pub fn predecessor(&self, state: &S) -> Option<&S> {
+        let id = *self.ids.get(state)?;
+        self.parents[id].map(|p| &self.states[p])
+    }
+
+    // Returns the states along the shortest path found from a start to
+    // `state`, including both endpoints.
+    pub fn path(&self, state: &S) -> Option<Vec<S>> {
+        self.path_from_id(*self.ids.get(state)?)
+    }
+
+    // Returns the first goal state reached and its distance.
+    pub fn goal(&self) -> Option<(&S, C)> {
+        self.goal.map(|id| (&self.states[id], self.costs[id]))
+    }
+
+    pub fn goal_path(&self) -> Option<Vec<S>> {
+        self.path_from_id(self.goal?)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&S, C)> {
+        self.states.iter().zip(self.costs.iter().copied())
+    }
+
+    fn path_from_id(&self, mut id: usize) -> Option<Vec<S>> {
+        let mut result = vec![self.states[id].clone()];
+        while let Some(parent) = self.parents[id] {
+            result.push(self.states[parent].clone());
+            id = parent;
+        }
+        result.reverse();
+        Some(result)
+    }
+}
+
+trait Frontier<C> {
+    fn push(&mut self, priority: C, id: usize);
+    fn pop(&mut self) -> Option<usize>;
+}
+
+struct Fifo(VecDeque<usize>);
+
+impl<C> Frontier<C> for Fifo {
+    fn push(&mut self, _: C, id: usize) {
+        self.0.push_back(id);
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        self.0.pop_front()
+    }
+}
+
+struct Heap<C>(BinaryHeap<Reverse<(C, usize)>>);
+
+impl<C: Ord> Frontier<C> for Heap<C> {
+    fn push(&mut self, priority: C, id: usize) {
+        self.0.push(Reverse((priority, id)));
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        self.0.pop().map(|Reverse((_, id))| id)
+    }
+}
+
+struct Buckets {
+    buckets: Vec<Vec<usize>>,
+    current: usize,
+    pending: usize,
+}
+
+impl Frontier<usize> for Buckets {
+    fn push(&mut self, priority: usize, id: usize) {
+        debug_assert!(priority - self.current < self.buckets.len());
+        let len = self.buckets.len();
+        self.buckets[priority % len].push(id);
+        self.pending += 1;
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        if self.pending == 0 {
+            return None;
+        }
+
+        let len = self.buckets.len();
+        loop {
+            if let Some(id) = self.buckets[self.current % len].pop() {
+                self.pending -= 1;
+                break Some(id);
+            }
+            self.current += 1;
+        }
+    }
+}
+
+struct ZeroOne<C> {
+    deque: VecDeque<(C, usize)>,
+    current: C,
+}
+
+impl<C: Cost> Frontier<C> for ZeroOne<C> {
+    fn push(&mut self, priority: C, id: usize) {
+        if priority == self.current {
+            self.deque.push_front((priority, id));
+        } else {
+            self.deque.push_back((priority, id));
+        }
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        let (cost, id) = self.deque.pop_front()?;
+        self.current = cost;
+        Some(id)
+    }
+}
+
+fn run<S, C, Q, I, F, H, G>(
+    starts: impl IntoIterator<Item = S>,
+    mut frontier: Q,
+    mut successors: F,
+    mut heuristic: H,
+    mut goal: G,
+) -> Search<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    Q: Frontier<C>,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    let mut search = Search::new();
+
+    for start in starts {
+        if let Some(id) = search.relax(start, C::default(), None) {
+            frontier.push(heuristic(&search.states[id]), id);
+        }
+    }
+
+    while let Some(id) = frontier.pop() {
+        if search.settled[id] {
+            continue;
+        }
+        search.settled[id] = true;
+
+        let state = search.states[id].clone();
+        if goal(&state) {
+            search.goal = Some(id);
+            break;
+        }
+
+        let cost = search.costs[id];
+        for (next, weight) in successors(&state) {
+            let next_cost = cost + weight;
+            if let Some(next_id) = search.relax(next, next_cost, Some(id)) {
+                let priority = next_cost + heuristic(&search.states[next_id]);
+                frontier.push(priority, next_id);
+            }
+        }
+    }
+
+    search
+}
+
+// Breadth-first search where every step costs one. Stops at the first state
+// for which `goal` returns true; pass `|_| false` to explore everything
+// reachable.
+pub fn bfs<S, I, F, G>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: F,
+    goal: G,
+) -> Search<S, usize>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    run(
+        starts,
+        Fifo(VecDeque::new()),
+        |s: &S| successors(s).into_iter().map(|n| (n, 1)),
+        |_| 0,
+        goal,
+    )
+}
+
+pub fn dijkstra<S, C, I, F, G>(
+    starts: impl IntoIterator<Item = S>,
+    successors: F,
+    goal: G,
+) -> Search<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    G: FnMut(&S) -> bool,
+{
+    run(
+        starts,
+        Heap(BinaryHeap::new()),
+        successors,
+        |_| C::default(),
+        goal,
+    )
+}
+
+// Dijkstra's algorithm using a circular bucket queue, for small integer edge
+// weights that never exceed `max_weight`.
+pub fn dijkstra_buckets<S, I, F, G>(
+    starts: impl IntoIterator<Item = S>,
+    max_weight: usize,
+    successors: F,
+    goal: G,
+) -> Search<S, usize>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, usize)>,
+    G: FnMut(&S) -> bool,
+{
+    run(
+        starts,
+        Buckets {
+            buckets: vec![Vec::new(); max_weight + 1],
+            current: 0,
+            pending: 0,
+        },
+        successors,
+        |_| 0,
+        goal,
+    )
+}
+
+// A* search. `heuristic` must never overestimate the remaining distance to a
+// goal and must be consistent for the first goal reached to be optimal.
+pub fn astar<S, C, I, F, H, G>(
+    starts: impl IntoIterator<Item = S>,
+    successors: F,
+    heuristic: H,
+    goal: G,
+) -> Search<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    run(starts, Heap(BinaryHeap::new()), successors, heuristic, goal)
+}
+
+// Breadth-first search over edges which cost either zero or one.
+pub fn zero_one_bfs<S, I, F, G>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: F,
+    goal: G,
+) -> Search<S, usize>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, bool)>,
+    G: FnMut(&S) -> bool,
+{
+    run(
+        starts,
+        ZeroOne {
+            deque: VecDeque::new(),
+            current: 0,
+        },
+        |s: &S| {
+            successors(s)
+                .into_iter()
+                .map(|(n, costly)| (n, usize::from(costly)))
+        },
+        |_| 0,
+        goal,
+    )
+}
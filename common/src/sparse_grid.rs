@@ -0,0 +1,223 @@
+use std::collections::{hash_map, HashMap};
+
+use crate::Grid;
+
+// An inclusive bounding box over signed coordinates.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Bounds {
+    pub min_x: i64,
+    pub min_y: i64,
+    pub max_x: i64,
+    pub max_y: i64,
+}
+
+impl Bounds {
+    pub fn new(min_x: i64, min_y: i64, max_x: i64, max_y: i64) -> Self {
+        Self {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        }
+    }
+
+    pub fn point(x: i64, y: i64) -> Self {
+        Self::new(x, y, x, y)
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max_x - self.min_x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max_y - self.min_y + 1) as usize
+    }
+
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        (self.min_x..=self.max_x).contains(&x)
+            && (self.min_y..=self.max_y).contains(&y)
+    }
+
+    pub fn include(&mut self, x: i64, y: i64) {
+        self.min_x = i64::min(self.min_x, x);
+        self.min_y = i64::min(self.min_y, y);
+        self.max_x = i64::max(self.max_x, x);
+        self.max_y = i64::max(self.max_y, y);
+    }
+
+    pub fn iter(&self) -> Cells {
+        Cells {
+            bounds: *self,
+            x: self.min_x,
+            y: self.min_y,
+        }
+    }
+}
+
+pub struct Cells {
+    bounds: Bounds,
+    x: i64,
+    y: i64,
+}
+
+impl Iterator for Cells {
+    type Item = (i64, i64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.y > self.bounds.max_y || self.bounds.min_x > self.bounds.max_x {
+            None
+        } else {
+            let result = Some((self.x, self.y));
+
+            if self.x == self.bounds.max_x {
+                self.x = self.bounds.min_x;
+                self.y += 1;
+            } else {
+                self.x += 1;
+            }
+
+            result
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<(i64, i64), T>,
+    bounds: Option<Bounds>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    // The smallest box containing every occupied cell, or `None` if the grid
+    // is empty.
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    pub fn get(&self, x: i64, y: i64) -> Option<&T> {
+        self.cells.get(&(x, y))
+    }
+
+    pub fn get_mut(&mut self, x: i64, y: i64) -> Option<&mut T> {
+        self.cells.get_mut(&(x, y))
+    }
+
+    pub fn set(&mut self, x: i64, y: i64, value: T) -> Option<T> {
+        match &mut self.bounds {
+            Some(bounds) => bounds.include(x, y),
+            None => self.bounds = Some(Bounds::point(x, y)),
+        }
+        self.cells.insert((x, y), value)
+    }
+
+    pub fn remove(&mut self, x: i64, y: i64) -> Option<T> {
+        let result = self.cells.remove(&(x, y))?;
+
+        // Only cells on the edge of the bounding box can shrink it
+        let bounds = self.bounds.unwrap();
+        if x == bounds.min_x
+            || x == bounds.max_x
+            || y == bounds.min_y
+            || y == bounds.max_y
+        {
+            self.bounds = self.cells.keys().fold(None, |b, &(x, y)| {
+                let mut b = b.unwrap_or(Bounds::point(x, y));
+                b.include(x, y);
+                Some(b)
+            });
+        }
+
+        Some(result)
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, (i64, i64), T> {
+        self.cells.iter()
+    }
+
+    pub fn adjacent(&self, x: i64, y: i64) -> Cells {
+        self.neighborhood(x, y, 1)
+    }
+
+    pub fn neighborhood(&self, x: i64, y: i64, radius: i64) -> Cells {
+        Bounds::new(x - radius, y - radius, x + radius, y + radius).iter()
+    }
+
+    // Renders the bounding box of the grid, passing `None` for unoccupied
+    // cells.
+    pub fn render<F: FnMut(Option<&T>) -> char>(&self, mut f: F) -> String {
+        let mut result = String::new();
+        if let Some(bounds) = self.bounds {
+            for y in bounds.min_y..=bounds.max_y {
+                for x in bounds.min_x..=bounds.max_x {
+                    result.push(f(self.get(x, y)));
+                }
+                result.push('\n');
+            }
+        }
+        result
+    }
+
+    // Converts to a dense grid covering the bounding box. The cell at
+    // `(min_x, min_y)` is moved to `(0, 0)` and unoccupied cells are filled
+    // with their default value.
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone + Default,
+    {
+        let Some(bounds) = self.bounds else {
+            return Grid::from_elements(0, 0, Vec::new());
+        };
+
+        let mut result = Grid::default(bounds.width(), bounds.height());
+        for (&(x, y), value) in self.cells.iter() {
+            result.set(
+                (x - bounds.min_x) as usize,
+                (y - bounds.min_y) as usize,
+                value.clone(),
+            );
+        }
+        result
+    }
+}
+
+impl<T> From<Grid<T>> for SparseGrid<T> {
+    fn from(grid: Grid<T>) -> Self {
+        let width = grid.width();
+        let mut result = Self::new();
+        for (i, value) in grid.into_elements().into_iter().enumerate() {
+            result.set((i % width) as i64, (i / width) as i64, value);
+        }
+        result
+    }
+}
+
+impl<T> FromIterator<((i64, i64), T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = ((i64, i64), T)>>(iter: I) -> Self {
+        let mut result = Self::new();
+        for ((x, y), value) in iter {
+            result.set(x, y, value);
+        }
+        result
+    }
+}
@@ -0,0 +1,270 @@
+use std::{
+    fmt,
+    ops::{
+        Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg,
+        Sub, SubAssign,
+    },
+    str::FromStr,
+};
+
+use crate::{Context as _, Error};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vec2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vec3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+fn abs<T: Copy + PartialOrd + Default + Neg<Output = T>>(value: T) -> T {
+    if value < T::default() {
+        -value
+    } else {
+        value
+    }
+}
+
+fn min<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+fn max<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+macro_rules! impl_vector {
+    ($name:ident, $len:literal, $($field:ident: $index:literal),*) => {
+        impl<T> $name<T> {
+            pub const fn new($($field: T),*) -> Self {
+                Self { $($field),* }
+            }
+
+            pub fn map<U, F: FnMut(T) -> U>(self, mut f: F) -> $name<U> {
+                $name { $($field: f(self.$field)),* }
+            }
+
+            pub fn zip<U, V, F: FnMut(T, U) -> V>(
+                self,
+                other: $name<U>,
+                mut f: F,
+            ) -> $name<V> {
+                $name { $($field: f(self.$field, other.$field)),* }
+            }
+
+            pub fn to_array(self) -> [T; $len] {
+                [$(self.$field),*]
+            }
+        }
+
+        impl<T: Copy> $name<T> {
+            pub const fn splat(value: T) -> Self {
+                Self { $($field: value),* }
+            }
+        }
+
+        impl<T: Copy + Add<Output = T> + Mul<Output = T>> $name<T> {
+            pub fn dot(self, other: Self) -> T {
+                let [first, rest @ ..] =
+                    self.zip(other, |a, b| a * b).to_array();
+                rest.into_iter().fold(first, |total, x| total + x)
+            }
+        }
+
+        impl<T: Copy + PartialOrd + Default + Add<Output = T> + Neg<Output = T>>
+            $name<T>
+        {
+            // The sum of the absolute values of the components.
+            pub fn manhattan(self) -> T {
+                let [first, rest @ ..] = self.map(abs).to_array();
+                rest.into_iter().fold(first, |total, x| total + x)
+            }
+
+            // The largest absolute value of any component.
+            pub fn chebyshev(self) -> T {
+                let [first, rest @ ..] = self.map(abs).to_array();
+                rest.into_iter().fold(first, max)
+            }
+        }
+
+        impl<T: PartialOrd> $name<T> {
+            pub fn component_min(self, other: Self) -> Self {
+                self.zip(other, min)
+            }
+
+            pub fn component_max(self, other: Self) -> Self {
+                self.zip(other, max)
+            }
+        }
+
+        impl<T> From<[T; $len]> for $name<T> {
+            fn from([$($field),*]: [T; $len]) -> Self {
+                Self { $($field),* }
+            }
+        }
+
+        impl<T> From<$name<T>> for [T; $len] {
+            fn from(value: $name<T>) -> Self {
+                value.to_array()
+            }
+        }
+
+        impl<T> Index<usize> for $name<T> {
+            type Output = T;
+
+            fn index(&self, index: usize) -> &Self::Output {
+                match index {
+                    $($index => &self.$field,)*
+                    _ => panic!("index {index} out of range for vector"),
+                }
+            }
+        }
+
+        impl<T> IndexMut<usize> for $name<T> {
+            fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+                match index {
+                    $($index => &mut self.$field,)*
+                    _ => panic!("index {index} out of range for vector"),
+                }
+            }
+        }
+
+        impl<T: Add<Output = T>> Add for $name<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                Self { $($field: self.$field + rhs.$field),* }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $name<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                Self { $($field: self.$field - rhs.$field),* }
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $name<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                Self { $($field: -self.$field),* }
+            }
+        }
+
+        impl<T: Copy + Mul<Output = T>> Mul<T> for $name<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self::Output {
+                Self { $($field: self.$field * rhs),* }
+            }
+        }
+
+        impl<T: Copy + Div<Output = T>> Div<T> for $name<T> {
+            type Output = Self;
+
+            fn div(self, rhs: T) -> Self::Output {
+                Self { $($field: self.$field / rhs),* }
+            }
+        }
+
+        impl<T: AddAssign> AddAssign for $name<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                $(self.$field += rhs.$field;)*
+            }
+        }
+
+        impl<T: SubAssign> SubAssign for $name<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                $(self.$field -= rhs.$field;)*
+            }
+        }
+
+        impl<T: Copy + MulAssign> MulAssign<T> for $name<T> {
+            fn mul_assign(&mut self, rhs: T) {
+                $(self.$field *= rhs;)*
+            }
+        }
+
+        impl<T: Copy + DivAssign> DivAssign<T> for $name<T> {
+            fn div_assign(&mut self, rhs: T) {
+                $(self.$field /= rhs;)*
+            }
+        }
+
+        // Parses comma-separated components, ignoring whitespace around them.
+        impl<T: FromStr> FromStr for $name<T>
+        where
+            Error: From<T::Err>,
+        {
+            type Err = Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let mut pieces = s.split(',').map(str::trim);
+                let result = Self {
+                    $(
+                        $field: pieces
+                            .next()
+                            .context(concat!(
+                                "expected ",
+                                stringify!($field),
+                                " component",
+                            ))?
+                            .parse()?,
+                    )*
+                };
+                if pieces.next().is_some() {
+                    crate::bail!("too many components in '{s}'");
+                }
+                Ok(result)
+            }
+        }
+    };
+}
+
+impl_vector!(Vec2, 2, x: 0, y: 1);
+impl_vector!(Vec3, 3, x: 0, y: 1, z: 2);
+
+impl<T: Copy + Sub<Output = T> + Mul<Output = T>> Vec2<T> {
+    // The z component of the cross product of the two vectors extended into
+    // three dimensions.
+    pub fn cross(self, other: Self) -> T {
+        self.x * other.y - self.y * other.x
+    }
+}
+
+impl<T: Copy + Sub<Output = T> + Mul<Output = T>> Vec3<T> {
+    pub fn cross(self, other: Self) -> Self {
+        Self {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Vec2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Vec3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
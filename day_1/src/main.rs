@@ -16,7 +16,7 @@ fn main() -> common::Result<()> {
                         .filter(char::is_ascii_digit)
                         .map(|c| c.to_digit(10).unwrap());
                     let first = digits.next().unwrap();
-                    let last = digits.last().unwrap_or(first);
+                    let last = digits.next_back().unwrap_or(first);
                     first * 10 + last
                 })
                 .sum::<u32>()
@@ -1,4 +1,4 @@
-use common::{bail, solve, Grid};
+use common::{bail, geometry::Polygon, solve, BitGrid, Grid, Vec2};
 
 const RIGHT_FLAG: isize = 0b0001;
 const UP_FLAG: isize = 0b0010;
@@ -143,24 +143,25 @@ fn replace_start(grid: &mut Grid<Tile>) -> (usize, usize) {
     (start_x, start_y)
 }
 
-fn mark_loop(
+// Returns the tiles of the loop in the order they are visited.
+fn trace_loop(
     grid: &Grid<Tile>,
     start_x: usize,
     start_y: usize,
-) -> (Grid<bool>, usize) {
-    let mut result = Grid::default(grid.width(), grid.height());
+) -> Vec<(usize, usize)> {
+    let mut visited = BitGrid::new(grid.width(), grid.height());
+    let mut result = Vec::new();
 
-    let mut length = 0;
     let mut x = start_x;
     let mut y = start_y;
 
-    while !result.get(x, y).unwrap() {
-        result.set(x, y, true);
-        length += 1;
+    while !visited.get(x, y).unwrap() {
+        visited.set(x, y, true);
+        result.push((x, y));
 
         for d in grid.get(x, y).unwrap().directions() {
             let (nx, ny) = d.add(x, y).unwrap();
-            if !result.get(nx, ny).unwrap() {
+            if !visited.get(nx, ny).unwrap() {
                 x = nx;
                 y = ny;
                 break;
@@ -168,7 +169,7 @@ fn mark_loop(
         }
     }
 
-    (result, length)
+    result
 }
 
 fn main() -> common::Result<()> {
@@ -177,44 +178,19 @@ fn main() -> common::Result<()> {
             let mut input = input.clone();
             let (start_x, start_y) = replace_start(&mut input);
 
-            mark_loop(&input, start_x, start_y).1 / 2
+            trace_loop(&input, start_x, start_y).len() / 2
         },
         |input| {
             let mut input = input.clone();
             let (start_x, start_y) = replace_start(&mut input);
 
-            let visited = mark_loop(&input, start_x, start_y).0;
-
-            let mut total = 0;
-            for y in 0..input.height() {
-                let mut inside = false;
-                let mut entered_top = false;
-                for x in 0..input.width() {
-                    if *visited.get(x, y).unwrap() {
-                        // This tile is on the loop
-                        match input.get(x, y).unwrap() {
-                            Tile::Horizontal | Tile::Ground | Tile::Start => (),
-                            Tile::Vertical => inside = !inside,
-                            Tile::ElbowNE => entered_top = true,
-                            Tile::ElbowNW => {
-                                if !entered_top {
-                                    inside = !inside;
-                                }
-                            }
-                            Tile::ElbowSE => entered_top = false,
-                            Tile::ElbowSW => {
-                                if entered_top {
-                                    inside = !inside;
-                                }
-                            }
-                        }
-                    } else if inside {
-                        total += 1;
-                    }
-                }
-            }
-
-            total
+            let path = trace_loop(&input, start_x, start_y);
+            Polygon::from_vertices(
+                path.into_iter()
+                    .map(|(x, y)| Vec2::new(x as i64, y as i64))
+                    .collect(),
+            )
+            .interior_points()
         },
     )
 }
@@ -1,6 +1,10 @@
-use std::{collections::HashMap, str::FromStr};
+use std::str::FromStr;
 
-use common::{bail, solve, Context, Lines};
+use common::{
+    bail,
+    memo::{Dense, Memo},
+    solve, Context, Lines,
+};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum Spring {
@@ -22,6 +26,10 @@ impl TryFrom<char> for Spring {
     }
 }
 
+// Counts keyed by (spring, group, run length)
+type Cache<'a> =
+    Memo<'a, (usize, usize, usize), usize, Dense<(usize, usize, usize), usize>>;
+
 struct Report {
     springs: Vec<Spring>,
     groups: Vec<usize>,
@@ -61,23 +69,22 @@ impl Report {
     }
 
     fn all_solutions(&self) -> usize {
-        let mut cache = HashMap::new();
-        self.solutions(0, 0, 0, &mut cache)
+        let max_group = self.groups.iter().copied().max().unwrap_or(0);
+        let mut memo = Memo::dense(
+            (self.springs.len() + 1, self.groups.len() + 1, max_group + 1),
+            |memo, (s, g, r)| self.solutions(memo, s, g, r),
+        );
+        memo.get_or_compute((0, 0, 0))
     }
 
-    #[inline]
     fn solutions(
         &self,
+        memo: &mut Cache,
         s: usize,
         g: usize,
         r: usize,
-        cache: &mut HashMap<(usize, usize, usize), usize>,
     ) -> usize {
-        if let Some(result) = cache.get(&(s, g, r)) {
-            return *result;
-        }
-
-        let result = if s == self.springs.len() {
+        if s == self.springs.len() {
             if g == self.groups.len()
                 || (g + 1 == self.groups.len() && self.groups[g] == r)
             {
@@ -88,46 +95,44 @@ impl Report {
         } else {
             match self.springs[s] {
                 Spring::Operational => {
-                    self.solutions_operational(s, g, r, cache)
+                    self.solutions_operational(memo, s, g, r)
                 }
-                Spring::Damaged => self.solutions_damaged(s, g, r, cache),
+                Spring::Damaged => self.solutions_damaged(memo, s, g, r),
                 Spring::Unknown => {
-                    self.solutions_operational(s, g, r, cache)
-                        + self.solutions_damaged(s, g, r, cache)
+                    self.solutions_operational(memo, s, g, r)
+                        + self.solutions_damaged(memo, s, g, r)
                 }
             }
-        };
-        cache.insert((s, g, r), result);
-        result
+        }
     }
 
     #[inline]
     fn solutions_operational(
         &self,
+        memo: &mut Cache,
         s: usize,
         g: usize,
         r: usize,
-        cache: &mut HashMap<(usize, usize, usize), usize>,
     ) -> usize {
         if r == 0 {
-            self.solutions(s + 1, g, 0, cache)
+            memo.get_or_compute((s + 1, g, 0))
         } else if r != self.groups[g] {
             0
         } else {
-            self.solutions(s + 1, g + 1, 0, cache)
+            memo.get_or_compute((s + 1, g + 1, 0))
         }
     }
 
     #[inline]
     fn solutions_damaged(
         &self,
+        memo: &mut Cache,
         s: usize,
         g: usize,
         r: usize,
-        cache: &mut HashMap<(usize, usize, usize), usize>,
     ) -> usize {
         if g < self.groups.len() && r < self.groups[g] {
-            self.solutions(s + 1, g, r + 1, cache)
+            memo.get_or_compute((s + 1, g, r + 1))
         } else {
             0
         }
@@ -1,6 +1,7 @@
-use std::collections::HashMap;
-
-use common::{bail, solve, Grid};
+use common::{
+    automaton::{Automaton, Gravity},
+    bail, cycle, solve, Grid,
+};
 
 #[derive(Clone, Copy, Hash, PartialEq, Eq)]
 enum Tile {
@@ -22,53 +23,8 @@ impl TryFrom<char> for Tile {
     }
 }
 
-#[derive(Clone, Copy)]
-enum Direction {
-    Right,
-    Up,
-    Left,
-    Down,
-}
-
-impl Direction {
-    const fn offset_x(self) -> isize {
-        match self {
-            Direction::Right => 1,
-            Direction::Left => -1,
-            Direction::Up | Direction::Down => 0,
-        }
-    }
-
-    const fn offset_y(self) -> isize {
-        match self {
-            Direction::Down => 1,
-            Direction::Up => -1,
-            Direction::Right | Direction::Left => 0,
-        }
-    }
-}
-
-fn tilt(grid: &mut Grid<Tile>, d: Direction) {
-    let mut moved = true;
-    while moved {
-        moved = false;
-        for (x, y) in grid.iter() {
-            if let (Some(dx), Some(dy)) = (
-                x.checked_add_signed(d.offset_x()),
-                y.checked_add_signed(d.offset_y()),
-            ) {
-                if *grid.get(x, y).unwrap() == Tile::Round
-                    && dx < grid.width()
-                    && dy < grid.height()
-                    && *grid.get(dx, dy).unwrap() == Tile::Empty
-                {
-                    grid.set(x, y, Tile::Empty);
-                    grid.set(dx, dy, Tile::Round);
-                    moved = true;
-                }
-            }
-        }
-    }
+fn tilt(rocks: &mut Automaton<Tile>, gravity: Gravity) {
+    rocks.sweep(gravity, |t| *t == Tile::Round, |t| *t == Tile::Empty);
 }
 
 fn load(grid: &Grid<Tile>) -> usize {
@@ -83,43 +39,29 @@ fn load(grid: &Grid<Tile>) -> usize {
     total
 }
 
-fn spin_cycle(grid: &mut Grid<Tile>) {
-    tilt(grid, Direction::Up);
-    tilt(grid, Direction::Left);
-    tilt(grid, Direction::Down);
-    tilt(grid, Direction::Right);
+fn spin_cycle(rocks: &mut Automaton<Tile>) {
+    tilt(rocks, Gravity::Up);
+    tilt(rocks, Gravity::Left);
+    tilt(rocks, Gravity::Down);
+    tilt(rocks, Gravity::Right);
 }
 
 fn main() -> common::Result<()> {
     solve(
         |input: &Grid<Tile>| {
-            let mut grid = input.clone();
-            tilt(&mut grid, Direction::Up);
-            load(&grid)
+            let mut rocks = Automaton::new(input.clone());
+            tilt(&mut rocks, Gravity::Up);
+            load(rocks.grid())
         },
         |input| {
-            const CYCLE_COUNT: usize = 1_000_000_000;
-
-            let mut grid = input.clone();
-            let mut seen = HashMap::new();
-            seen.insert(grid.clone(), 0);
-
-            let mut cycle = 0;
-            while cycle < CYCLE_COUNT {
-                spin_cycle(&mut grid);
-                cycle += 1;
-
-                if let Some(last_seen) = seen.get(&grid) {
-                    // Jump ahead
-                    let cycle_length = cycle - last_seen;
-                    let remaining = CYCLE_COUNT - cycle;
-                    cycle += remaining / cycle_length * cycle_length;
-                } else {
-                    seen.insert(grid.clone(), cycle);
-                }
-            }
+            let rocks = cycle::state_after_by(
+                Automaton::new(input.clone()),
+                1_000_000_000,
+                spin_cycle,
+                |rocks| rocks.grid(),
+            );
 
-            load(&grid)
+            load(rocks.grid())
         },
     )
 }
@@ -1,6 +1,4 @@
-use std::collections::VecDeque;
-
-use common::{solve, Grid};
+use common::{search, solve, Grid};
 
 struct Tile {
     heat_loss: usize,
@@ -16,7 +14,7 @@ impl TryFrom<char> for Tile {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Debug)]
 enum Direction {
     Right,
     Up,
@@ -53,106 +51,37 @@ impl Direction {
     }
 }
 
-const MAX_TIME: usize = 10;
-
-#[derive(Default)]
-struct Node {
-    min_costs: [[Option<usize>; MAX_TIME]; 4],
-}
-
-fn min_cost(input: &Grid<Tile>, min_time: usize, max_time: usize) -> usize {
-    let mut nodes = Grid::<Node>::default(input.width(), input.height());
-    let mut frontier = VecDeque::new();
-
-    explore(
-        input,
-        &mut nodes,
-        &mut frontier,
-        0,
-        0,
-        0,
-        Direction::Right,
-        0,
+fn min_cost(input: &Grid<Tile>, min_run: usize, max_run: usize) -> usize {
+    let end_x = input.width() - 1;
+    let end_y = input.height() - 1;
+
+    let search = search::dijkstra_buckets(
+        [(0, 0, Direction::Right, 0), (0, 0, Direction::Up, 0)],
+        9,
+        |&(x, y, d, run)| {
+            let turn = run >= min_run;
+            [
+                (run < max_run).then_some((d, run + 1)),
+                turn.then_some((d.rotate_cw(), 1)),
+                turn.then_some((d.rotate_ccw(), 1)),
+            ]
+            .into_iter()
+            .flatten()
+            .filter_map(move |(d, run)| {
+                let (nx, ny) = d.offset(x, y)?;
+                let tile = input.get(nx, ny)?;
+                Some(((nx, ny, d, run), tile.heat_loss))
+            })
+        },
+        |&(x, y, _, run)| x == end_x && y == end_y && run >= min_run,
     );
-    explore(input, &mut nodes, &mut frontier, 0, 0, 0, Direction::Up, 0);
-
-    while let Some((c, x, y, d, t)) = frontier.pop_front() {
-        if nodes.get(x, y).unwrap().min_costs[d as usize][t]
-            .as_ref()
-            .is_some_and(|m| *m < c)
-        {
-            continue;
-        }
-
-        if t + 1 < max_time {
-            explore(input, &mut nodes, &mut frontier, c, x, y, d, t + 1);
-        }
-        if t >= min_time {
-            explore(
-                input,
-                &mut nodes,
-                &mut frontier,
-                c,
-                x,
-                y,
-                d.rotate_cw(),
-                0,
-            );
-            explore(
-                input,
-                &mut nodes,
-                &mut frontier,
-                c,
-                x,
-                y,
-                d.rotate_ccw(),
-                0,
-            );
-        }
-    }
 
-    nodes
-        .get(input.width() - 1, input.height() - 1)
-        .unwrap()
-        .min_costs
-        .iter()
-        .flat_map(|x| x[min_time..].iter().filter_map(|n| *n))
-        .min()
-        .unwrap()
-}
-
-// I am too done with this problem to address this lint
-#[allow(clippy::too_many_arguments)]
-fn explore(
-    input: &Grid<Tile>,
-    nodes: &mut Grid<Node>,
-    frontier: &mut VecDeque<(usize, usize, usize, Direction, usize)>,
-    c: usize,
-    x: usize,
-    y: usize,
-    d: Direction,
-    nt: usize,
-) {
-    if let Some((nx, ny)) = d.offset(x, y) {
-        if nx >= input.width() || ny >= input.height() {
-            return;
-        }
-
-        let best =
-            &mut nodes.get_mut(nx, ny).unwrap().min_costs[d as usize][nt];
-        let current = c + input.get(nx, ny).unwrap().heat_loss;
-        if best.as_ref().is_some_and(|n| *n <= current) {
-            return;
-        }
-
-        *best = Some(current);
-        frontier.push_back((current, nx, ny, d, nt));
-    }
+    search.goal().unwrap().1
 }
 
 fn main() -> common::Result<()> {
     solve(
-        |input: &Grid<Tile>| min_cost(input, 0, 3),
-        |input| min_cost(input, 3, 10),
+        |input: &Grid<Tile>| min_cost(input, 1, 3),
+        |input| min_cost(input, 4, 10),
     )
 }
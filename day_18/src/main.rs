@@ -1,31 +1,6 @@
-use std::{
-    ops::{Add, Div, Mul},
-    str::FromStr,
-};
+use std::str::FromStr;
 
-use common::{bail, solve, Context as _, Lines};
-
-#[derive(Clone, Copy, PartialEq, Eq)]
-struct Vec2i {
-    x: isize,
-    y: isize,
-}
-
-impl Vec2i {
-    const ZERO: Self = Vec2i::new(0, 0);
-
-    const fn new(x: isize, y: isize) -> Self {
-        Self { x, y }
-    }
-}
-
-impl Add for Vec2i {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self::new(self.x + rhs.x, self.y + rhs.y)
-    }
-}
+use common::{bail, geometry::Polygon, try_solve, Context as _, Lines, Vec2};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum Direction {
@@ -36,30 +11,12 @@ enum Direction {
 }
 
 impl Direction {
-    fn rotate_ccw(self) -> Self {
+    fn delta(self) -> Vec2<i64> {
         match self {
-            Direction::Right => Self::Up,
-            Direction::Up => Self::Left,
-            Direction::Left => Self::Down,
-            Direction::Down => Self::Right,
-        }
-    }
-
-    fn rotate_cw(self) -> Self {
-        match self {
-            Direction::Right => Self::Down,
-            Direction::Up => Self::Right,
-            Direction::Left => Self::Up,
-            Direction::Down => Self::Left,
-        }
-    }
-
-    fn apply(self, distance: isize) -> Vec2i {
-        match self {
-            Self::Right => Vec2i::new(distance, 0),
-            Self::Up => Vec2i::new(0, distance),
-            Self::Left => Vec2i::new(-distance, 0),
-            Self::Down => Vec2i::new(0, -distance),
+            Self::Right => Vec2::new(1, 0),
+            Self::Up => Vec2::new(0, 1),
+            Self::Left => Vec2::new(-1, 0),
+            Self::Down => Vec2::new(0, -1),
         }
     }
 }
@@ -79,7 +36,7 @@ impl FromStr for Direction {
 }
 
 struct HexCode {
-    distance: isize,
+    distance: i64,
     direction: Direction,
 }
 
@@ -106,7 +63,7 @@ impl FromStr for HexCode {
                 .take(5)
                 .map(hex_to_value)
                 .try_fold(0, |a, v| {
-                    Result::<_, common::Error>::Ok(a << 4 | v? as isize)
+                    Result::<_, common::Error>::Ok(a << 4 | v? as i64)
                 })?,
             direction: match s.chars().last() {
                 None => bail!("expected direction"),
@@ -122,7 +79,7 @@ impl FromStr for HexCode {
 
 struct Command {
     direction: Direction,
-    distance: isize,
+    distance: i64,
     hex: HexCode,
 }
 
@@ -146,138 +103,33 @@ impl FromStr for Command {
     }
 }
 
-#[derive(Debug)]
-struct Fraction {
-    n: isize,
-    d: usize,
-}
-
-impl Fraction {
-    const ZERO: Self = Fraction::new(0, 1);
-
-    const fn new(n: isize, d: usize) -> Self {
-        Self { n, d }
-    }
-
-    fn reduce(self) -> Self {
-        let x = gcd(self.n.unsigned_abs(), self.d);
-        Self {
-            n: self.n / x as isize,
-            d: self.d / x,
-        }
-    }
-}
-
-fn gcd(mut a: usize, mut b: usize) -> usize {
-    while b != 0 {
-        let t = b;
-        b = a % b;
-        a = t;
-    }
-    a
-}
-
-impl Add for Fraction {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        let x = gcd(self.d, rhs.d);
-        Self {
-            n: self.n * (rhs.d / x) as isize + rhs.n * (self.d / x) as isize,
-            d: self.d * rhs.d / x,
-        }
-        .reduce()
-    }
-}
-
-impl Mul for Fraction {
-    type Output = Fraction;
-
-    fn mul(self, rhs: Self) -> Self::Output {
-        Self {
-            n: self.n * rhs.n,
-            d: self.d * rhs.d,
-        }
-        .reduce()
-    }
-}
-
-impl Div<usize> for Fraction {
-    type Output = Fraction;
-
-    fn div(self, rhs: usize) -> Self::Output {
-        Self {
-            n: self.n,
-            #[allow(clippy::suspicious_arithmetic_impl)]
-            d: self.d * rhs,
-        }
-        .reduce()
-    }
-}
-
-fn shoelace(a: Vec2i, b: Vec2i) -> Fraction {
-    Fraction::new((a.y + b.y) * (a.x - b.x), 1) / 2
+// The number of cubic meters dug out, counting both the trench along the
+// walk and the lagoon inside it.
+fn lagoon(
+    moves: impl Iterator<Item = (Direction, i64)>,
+) -> common::Result<i128> {
+    let polygon =
+        Polygon::from_walk(moves.map(|(d, distance)| (d.delta(), distance)))?;
+    Ok(polygon.interior_points() + polygon.boundary_points())
 }
 
 fn main() -> common::Result<()> {
-    solve(
+    try_solve(
         |input: &Lines<Command>| {
-            let mut area = Fraction::ZERO;
-            let mut p = Vec2i::ZERO;
-            for (i, line) in input.lines.iter().enumerate() {
-                let add = if line.direction.rotate_cw()
-                    == input.lines[(i + 1) % input.lines.len()].direction
-                {
-                    1
-                } else {
-                    0
-                } + if line.direction.rotate_ccw()
-                    == input.lines
-                        [(input.lines.len() + i - 1) % input.lines.len()]
-                    .direction
-                {
-                    1
-                } else {
-                    0
-                };
-
-                let n = p + line.direction.apply(line.distance + add - 1);
-                area = area + shoelace(p, n);
-                p = n;
-            }
-
-            assert_eq!(area.d, 1);
-            -area.n
+            lagoon(
+                input
+                    .lines
+                    .iter()
+                    .map(|line| (line.direction, line.distance)),
+            )
         },
         |input| {
-            let mut area = Fraction::ZERO;
-            let mut p = Vec2i::ZERO;
-            for (i, line) in input.lines.iter().enumerate() {
-                let add = if line.hex.direction.rotate_cw()
-                    == input.lines[(i + 1) % input.lines.len()].hex.direction
-                {
-                    1
-                } else {
-                    0
-                } + if line.hex.direction.rotate_ccw()
-                    == input.lines
-                        [(input.lines.len() + i - 1) % input.lines.len()]
-                    .hex
-                    .direction
-                {
-                    1
-                } else {
-                    0
-                };
-
-                let n =
-                    p + line.hex.direction.apply(line.hex.distance + add - 1);
-                area = area + shoelace(p, n);
-                p = n;
-            }
-
-            assert_eq!(area.d, 1);
-            -area.n
+            lagoon(
+                input
+                    .lines
+                    .iter()
+                    .map(|line| (line.hex.direction, line.hex.distance)),
+            )
         },
     )
 }
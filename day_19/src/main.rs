@@ -1,7 +1,14 @@
-use std::{collections::HashMap, ops::Range, str::FromStr};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
 
-use common::{bail, solve, Context as _};
+use common::{
+    bail, graph::GraphBuilder, interval::Hyperrect, solve, Context as _,
+};
 
+#[derive(Clone, Copy)]
 enum Operation {
     LessThan,
     GreaterThan,
@@ -15,22 +22,24 @@ impl Operation {
         }
     }
 
-    // Splits range into (true, false)
+    // Splits parts along axis into (true, false)
     fn split(
         &self,
-        range: Range<usize>,
+        parts: &Parts,
+        axis: usize,
         value: usize,
-    ) -> (Range<usize>, Range<usize>) {
+    ) -> (Parts, Parts) {
         match self {
-            Self::LessThan => split_range(range, value),
+            Self::LessThan => parts.split(axis, value),
             Self::GreaterThan => {
-                let (f, t) = split_range(range, value + 1);
+                let (f, t) = parts.split(axis, value + 1);
                 (t, f)
             }
         }
     }
 }
 
+#[derive(Clone, Copy)]
 enum Field {
     X,
     M,
@@ -47,8 +56,18 @@ impl Field {
             Self::S => part.s,
         }
     }
+
+    fn axis(&self) -> usize {
+        match self {
+            Self::X => 0,
+            Self::M => 1,
+            Self::A => 2,
+            Self::S => 3,
+        }
+    }
 }
 
+#[derive(Clone, Copy)]
 struct Condition {
     field: Field,
     operation: Operation,
@@ -61,49 +80,19 @@ impl Condition {
     }
 
     // Splits parts into (true, false)
-    fn split(&self, parts: AllParts) -> (AllParts, AllParts) {
-        match self.field {
-            Field::X => {
-                let (t, f) = self.operation.split(parts.x.clone(), self.value);
-                (
-                    AllParts {
-                        x: t,
-                        ..parts.clone()
-                    },
-                    AllParts { x: f, ..parts },
-                )
-            }
-            Field::M => {
-                let (t, f) = self.operation.split(parts.m.clone(), self.value);
-                (
-                    AllParts {
-                        m: t,
-                        ..parts.clone()
-                    },
-                    AllParts { m: f, ..parts },
-                )
-            }
-            Field::A => {
-                let (t, f) = self.operation.split(parts.a.clone(), self.value);
-                (
-                    AllParts {
-                        a: t,
-                        ..parts.clone()
-                    },
-                    AllParts { a: f, ..parts },
-                )
-            }
-            Field::S => {
-                let (t, f) = self.operation.split(parts.s.clone(), self.value);
-                (
-                    AllParts {
-                        s: t,
-                        ..parts.clone()
-                    },
-                    AllParts { s: f, ..parts },
-                )
-            }
-        }
+    fn split(&self, parts: &Parts) -> (Parts, Parts) {
+        self.operation.split(parts, self.field.axis(), self.value)
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let field = ["x", "m", "a", "s"][self.field.axis()];
+        let operation = match self.operation {
+            Operation::LessThan => '<',
+            Operation::GreaterThan => '>',
+        };
+        write!(f, "{field}{operation}{}", self.value)
     }
 }
 
@@ -172,17 +161,6 @@ struct Workflow {
     default: Destination,
 }
 
-impl Workflow {
-    fn destination(&self, part: &Part) -> &Destination {
-        for rule in self.rules.iter() {
-            if rule.condition.applies(part) {
-                return &rule.destination;
-            }
-        }
-        &self.default
-    }
-}
-
 impl FromStr for Workflow {
     type Err = common::Error;
 
@@ -236,15 +214,25 @@ impl FromStr for Part {
 }
 
 struct Input {
-    workflows: HashMap<String, Workflow>,
+    workflows: Vec<(String, Workflow)>,
+    tree: Tree,
     parts: Vec<Part>,
 }
 
+impl Input {
+    fn describe(&self, id: usize) -> String {
+        let decision = &self.tree.decisions[id];
+        let (workflow, rule) = decision.origin;
+        let name = &self.workflows[workflow].0;
+        format!("{name}:{rule} {}", decision.condition)
+    }
+}
+
 impl common::Input for Input {
     fn parse_reader<R: std::io::prelude::BufRead>(
         reader: R,
     ) -> common::Result<Self> {
-        let mut workflows = HashMap::new();
+        let mut workflows = Vec::new();
         let mut parts = Vec::new();
         let mut lines = reader.lines();
 
@@ -257,12 +245,12 @@ impl common::Input for Input {
 
             let (name, rest) =
                 line.split_once('{').context("expected name prefix")?;
-            workflows.insert(
+            workflows.push((
                 name.to_string(),
                 rest.strip_suffix('}')
                     .context("expected workflow to end with }")?
                     .parse()?,
-            );
+            ));
         }
 
         for line in lines {
@@ -270,56 +258,220 @@ impl common::Input for Input {
             parts.push(line.parse()?);
         }
 
-        Ok(Self { workflows, parts })
+        let tree = Tree::compile(&workflows)?;
+        Ok(Self {
+            workflows,
+            tree,
+            parts,
+        })
+    }
+
+    // The decision tree, with the unreachable rules highlighted
+    fn to_dot(&self) -> Option<String> {
+        let mut graph = GraphBuilder::new();
+        for id in 0..self.tree.decisions.len() {
+            graph.node(&self.describe(id));
+        }
+        let accept = graph.node("A");
+        let reject = graph.node("R");
+        let id = |target| match target {
+            Target::Accept => accept,
+            Target::Reject => reject,
+            Target::Decision(id) => id,
+        };
+        for (from, decision) in self.tree.decisions.iter().enumerate() {
+            graph.add_edge(from, id(decision.pass), "pass");
+            graph.add_edge(from, id(decision.fail), "fail");
+        }
+        let graph = graph.directed();
+
+        let dot = graph
+            .dot()
+            .labeled()
+            .weighted()
+            .highlight_nodes(self.tree.unreachable())
+            .to_string();
+        Some(dot)
+    }
+
+    // The unreachable rules, then every box of accepted parts
+    fn report(&self) -> Option<String> {
+        let mut report = String::new();
+        for id in self.tree.unreachable() {
+            report.push_str(&format!("unreachable {}\n", self.describe(id)));
+        }
+        for parts in self.tree.accepted(all_parts()) {
+            let [x, m, a, s] = parts.ranges();
+            report.push_str(&format!(
+                "accepted x={x:?} m={m:?} a={a:?} s={s:?}\n"
+            ));
+        }
+        Some(report)
     }
 }
 
-fn split_range(range: Range<usize>, x: usize) -> (Range<usize>, Range<usize>) {
-    (
-        range.start..usize::max(range.start, x),
-        usize::min(range.end, x)..range.end,
-    )
+// Ranges of x, m, a and s ratings
+type Parts = Hyperrect<usize, 4>;
+
+#[derive(Clone, Copy)]
+enum Target {
+    Accept,
+    Reject,
+    Decision(usize),
 }
 
-#[derive(Clone)]
-struct AllParts {
-    x: Range<usize>,
-    m: Range<usize>,
-    a: Range<usize>,
-    s: Range<usize>,
+// One rule of one workflow. Parts which meet the condition go to `pass`,
+// and the rest go on to `fail`, which is the next rule of the workflow or
+// its default.
+struct Decision {
+    condition: Condition,
+    pass: Target,
+    fail: Target,
+    // The index of the workflow and of the rule within it
+    origin: (usize, usize),
 }
 
-impl AllParts {
-    fn count(&self) -> usize {
-        (self.x.end - self.x.start)
-            * (self.m.end - self.m.start)
-            * (self.a.end - self.a.start)
-            * (self.s.end - self.s.start)
-    }
+// Every workflow compiled into a single tree of decisions, starting from the
+// first rule of "in". Parts only ever move forward through it.
+struct Tree {
+    decisions: Vec<Decision>,
+    root: Target,
 }
 
-fn count_accepted(
-    workflows: &HashMap<String, Workflow>,
-    w: &str,
-    mut parts: AllParts,
-) -> usize {
-    let workflow = &workflows[w];
-    let mut total = 0;
-    for rule in workflow.rules.iter() {
-        let (t, f) = rule.condition.split(parts);
-        total += match &rule.destination {
-            Destination::Accept => t.count(),
-            Destination::Reject => 0,
-            Destination::Workflow(w) => count_accepted(workflows, w, t),
+impl Tree {
+    fn compile(workflows: &[(String, Workflow)]) -> common::Result<Self> {
+        // Each workflow's rules get consecutive ids
+        let mut ids = HashMap::new();
+        let mut starts = Vec::with_capacity(workflows.len());
+        let mut count = 0;
+        for (index, (name, workflow)) in workflows.iter().enumerate() {
+            if ids.insert(name.as_str(), index).is_some() {
+                bail!("workflow {name} is defined twice");
+            }
+            starts.push(count);
+            count += workflow.rules.len();
+        }
+
+        let mut calls = GraphBuilder::new();
+        for (name, _) in workflows.iter() {
+            calls.node(name.as_str());
+        }
+        let mut target = |from: &str, destination: &Destination| {
+            Ok(match destination {
+                Destination::Accept => Target::Accept,
+                Destination::Reject => Target::Reject,
+                Destination::Workflow(to) => {
+                    let Some(&index) = ids.get(to.as_str()) else {
+                        bail!("workflow {from} sends parts to undefined {to}");
+                    };
+                    calls.add_labeled_edge(from, to.as_str(), ());
+                    Target::Decision(starts[index])
+                }
+            })
         };
-        parts = f;
+
+        let mut decisions = Vec::with_capacity(count);
+        for (index, (name, workflow)) in workflows.iter().enumerate() {
+            let default = target(name, &workflow.default)?;
+            let last = workflow.rules.len() - 1;
+            for (rule_index, rule) in workflow.rules.iter().enumerate() {
+                let fail = if rule_index == last {
+                    default
+                } else {
+                    Target::Decision(decisions.len() + 1)
+                };
+                decisions.push(Decision {
+                    condition: rule.condition,
+                    pass: target(name, &rule.destination)?,
+                    fail,
+                    origin: (index, rule_index),
+                });
+            }
+        }
+
+        let calls = calls.directed();
+        if let Some(cycle) = calls
+            .strongly_connected_components()
+            .into_iter()
+            .find(|c| c.len() > 1 || calls.neighbors(c[0]).any(|n| n == c[0]))
+        {
+            let names = cycle.iter().map(|&w| workflows[w].0.as_str());
+            bail!(
+                "workflows send parts round in a cycle: {}",
+                names.collect::<Vec<_>>().join(", "),
+            );
+        }
+
+        let root = ids.get("in").context("expected an in workflow")?;
+        Ok(Self {
+            decisions,
+            root: Target::Decision(starts[*root]),
+        })
+    }
+
+    fn evaluate(&self, part: &Part) -> bool {
+        let mut target = self.root;
+        loop {
+            match target {
+                Target::Accept => break true,
+                Target::Reject => break false,
+                Target::Decision(id) => {
+                    let decision = &self.decisions[id];
+                    target = if decision.condition.applies(part) {
+                        decision.pass
+                    } else {
+                        decision.fail
+                    };
+                }
+            }
+        }
     }
-    total
-        + match &workflow.default {
-            Destination::Accept => parts.count(),
-            Destination::Reject => 0,
-            Destination::Workflow(w) => count_accepted(workflows, w, parts),
+
+    // Sends every possible part through the tree, calling `visit` with each
+    // box of parts which arrives at a target.
+    fn walk(&self, parts: Parts, mut visit: impl FnMut(Target, &Parts)) {
+        let mut pending = vec![(self.root, parts)];
+        while let Some((target, parts)) = pending.pop() {
+            if parts.is_empty() {
+                continue;
+            }
+            visit(target, &parts);
+            if let Target::Decision(id) = target {
+                let decision = &self.decisions[id];
+                let (pass, fail) = decision.condition.split(&parts);
+                pending.push((decision.fail, fail));
+                pending.push((decision.pass, pass));
+            }
         }
+    }
+
+    // The disjoint boxes of parts which are accepted.
+    fn accepted(&self, parts: Parts) -> Vec<Parts> {
+        let mut accepted = Vec::new();
+        self.walk(parts, |target, parts| {
+            if let Target::Accept = target {
+                accepted.push(parts.clone());
+            }
+        });
+        accepted
+    }
+
+    // The rules which no part with ratings from 1 to 4000 both reaches and
+    // meets the condition of.
+    fn unreachable(&self) -> Vec<usize> {
+        let mut used = vec![false; self.decisions.len()];
+        self.walk(all_parts(), |target, parts| {
+            if let Target::Decision(id) = target {
+                let (pass, _) = self.decisions[id].condition.split(parts);
+                used[id] |= !pass.is_empty();
+            }
+        });
+        (0..used.len()).filter(|&id| !used[id]).collect()
+    }
+}
+
+fn all_parts() -> Parts {
+    Parts::new([1..4001, 1..4001, 1..4001, 1..4001])
 }
 
 fn main() -> common::Result<()> {
@@ -328,27 +480,17 @@ fn main() -> common::Result<()> {
             input
                 .parts
                 .iter()
-                .filter(|part| {
-                    let mut workflow = "in";
-                    loop {
-                        match input.workflows[workflow].destination(part) {
-                            Destination::Accept => break true,
-                            Destination::Reject => break false,
-                            Destination::Workflow(w) => workflow = w,
-                        }
-                    }
-                })
+                .filter(|part| input.tree.evaluate(part))
                 .map(|p| p.x + p.m + p.a + p.s)
                 .sum::<usize>()
         },
         |input| {
-            let start = AllParts {
-                x: 1..4001,
-                m: 1..4001,
-                a: 1..4001,
-                s: 1..4001,
-            };
-            count_accepted(&input.workflows, "in", start)
+            input
+                .tree
+                .accepted(all_parts())
+                .iter()
+                .map(Parts::volume)
+                .sum::<usize>()
         },
     )
 }
@@ -3,18 +3,35 @@
 // this already happened on day 8 this year, and i'm really disappointed that it
 // happened again.
 
-use std::{
-    collections::{HashMap, VecDeque},
-    str::FromStr,
-};
+use std::{collections::VecDeque, io::BufRead, ops::Range, str::FromStr};
 
-use common::{bail, solve, Context, Lines};
+use common::{
+    bail,
+    cycle::{brent, Cycle},
+    graph::GraphBuilder,
+    math::crt,
+    try_solve, Context, Input, Lines,
+};
 
-#[derive(PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 enum Kind {
     Broadcaster,
     FlipFlop,
     Conjunction,
+    // Presses send a low pulse to the broadcaster
+    Button,
+    // Named as an output, but never defined
+    Output,
+}
+
+impl Kind {
+    fn prefix(self) -> &'static str {
+        match self {
+            Kind::FlipFlop => "%",
+            Kind::Conjunction => "&",
+            _ => "",
+        }
+    }
 }
 
 struct Module {
@@ -47,179 +64,454 @@ impl FromStr for Module {
     }
 }
 
-#[allow(clippy::type_complexity)]
-fn setup(
-    input: &[Module],
-) -> (
-    HashMap<String, &Module>,
-    HashMap<String, Vec<String>>,
-    HashMap<String, bool>,
-) {
-    let modules = input
-        .iter()
-        .map(|module| (module.name.clone(), module))
-        .collect::<HashMap<_, _>>();
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Pulse {
+    from: usize,
+    to: usize,
+    high: bool,
+}
+
+// Sees every pulse as it is delivered.
+trait Observer {
+    fn observe(&mut self, pulse: Pulse);
+}
+
+// Ignores every pulse
+impl Observer for () {
+    fn observe(&mut self, _: Pulse) {}
+}
+
+// Records every pulse in order
+impl Observer for Vec<Pulse> {
+    fn observe(&mut self, pulse: Pulse) {
+        self.push(pulse);
+    }
+}
 
-    let mut inputs = HashMap::new();
-    let mut state = HashMap::new();
+#[derive(Default)]
+struct Counter {
+    low: usize,
+    high: usize,
+}
 
-    for module in input.iter() {
-        state.insert(module.name.clone(), false);
-        for output in module.outputs.iter() {
-            inputs
-                .entry(output.clone())
-                .or_insert(Vec::new())
-                .push(module.name.clone());
+impl Observer for Counter {
+    fn observe(&mut self, pulse: Pulse) {
+        if pulse.high {
+            self.high += 1;
+        } else {
+            self.low += 1;
         }
     }
+}
 
-    (modules, inputs, state)
+// Notes which of a set of modules have sent a high pulse since the last
+// `clear`.
+struct Watch {
+    sources: Vec<usize>,
+    fired: Vec<bool>,
 }
 
-#[allow(clippy::too_many_arguments)]
-fn step_one<'a>(
-    modules: &'a HashMap<String, &Module>,
-    inputs: &HashMap<String, Vec<String>>,
-    state: &mut HashMap<String, bool>,
-    pending: &mut VecDeque<(&'a str, bool)>,
-    total_high: &mut usize,
-    total_low: &mut usize,
-    target: &str,
-    value: bool,
-) {
-    if value {
-        *total_high += 1;
-    } else {
-        *total_low += 1;
+impl Watch {
+    fn new(sources: Vec<usize>) -> Self {
+        let fired = vec![false; sources.len()];
+        Self { sources, fired }
     }
 
-    if !modules.contains_key(target) {
-        return;
+    fn clear(&mut self) {
+        self.fired.fill(false);
     }
+}
 
-    let module = &modules[target];
-    match module.kind {
-        Kind::Broadcaster => {
-            for output in module.outputs.iter() {
-                pending.push_back((output, value));
+impl Observer for Watch {
+    fn observe(&mut self, pulse: Pulse) {
+        if pulse.high {
+            for (source, fired) in
+                self.sources.iter().zip(self.fired.iter_mut())
+            {
+                *fired |= *source == pulse.from;
             }
         }
-        Kind::FlipFlop => {
-            if !value {
-                let s = state.get_mut(target).unwrap();
-                *s = !*s;
-                for output in module.outputs.iter() {
-                    pending.push_back((output, *s));
-                }
+    }
+}
+
+// The modules wired together with their state. Every wire has a slot in
+// `memory` holding the last pulse sent along it, which conjunctions use.
+#[derive(Clone)]
+struct Circuit {
+    names: Vec<String>,
+    kinds: Vec<Kind>,
+    // The modules each module sends to, with the slot of each wire
+    outputs: Vec<Vec<(usize, usize)>>,
+    inputs: Vec<Vec<usize>>,
+    // The slots of the wires into each module
+    slots: Vec<Range<usize>>,
+    memory: Vec<bool>,
+    // The number of wires into each module whose last pulse was high
+    highs: Vec<usize>,
+    on: Vec<bool>,
+    button: usize,
+    pending: VecDeque<(Pulse, usize)>,
+}
+
+impl Circuit {
+    fn new(modules: &[Module]) -> common::Result<Self> {
+        let mut names = Vec::new();
+        let mut kinds = Vec::new();
+        for module in modules.iter() {
+            if names.contains(&module.name) {
+                bail!("module {} is defined twice", module.name);
             }
+            names.push(module.name.clone());
+            kinds.push(module.kind);
         }
-        Kind::Conjunction => {
-            *state.get_mut(target).unwrap() =
-                !inputs[target].iter().all(|i| state[i.as_str()]);
+        let broadcaster = kinds
+            .iter()
+            .position(|&kind| kind == Kind::Broadcaster)
+            .context("expected a broadcaster")?;
+        let button = names.len();
+        names.push("button".to_string());
+        kinds.push(Kind::Button);
+
+        let mut wires = vec![(button, broadcaster)];
+        for (from, module) in modules.iter().enumerate() {
             for output in module.outputs.iter() {
-                pending.push_back((output, state[target]));
+                let to = match names.iter().position(|name| name == output) {
+                    Some(to) => to,
+                    None => {
+                        names.push(output.clone());
+                        kinds.push(Kind::Output);
+                        names.len() - 1
+                    }
+                };
+                wires.push((from, to));
             }
         }
-    }
-}
 
-fn step(
-    modules: &HashMap<String, &Module>,
-    inputs: &HashMap<String, Vec<String>>,
-    state: &mut HashMap<String, bool>,
-) -> (usize, usize) {
-    let mut pending = VecDeque::new();
-    let mut total_low = 0;
-    let mut total_high = 0;
+        // Number the wires by destination so each module's are contiguous
+        let mut inputs = vec![Vec::new(); names.len()];
+        for &(from, to) in wires.iter() {
+            inputs[to].push(from);
+        }
+        let mut slots = Vec::with_capacity(names.len());
+        let mut next_slot = Vec::with_capacity(names.len());
+        let mut start = 0;
+        for inputs in inputs.iter() {
+            slots.push(start..start + inputs.len());
+            next_slot.push(start);
+            start += inputs.len();
+        }
+        let mut outputs = vec![Vec::new(); names.len()];
+        for (from, to) in wires {
+            outputs[from].push((to, next_slot[to]));
+            next_slot[to] += 1;
+        }
 
-    pending.push_back(("broadcaster", false));
-    while let Some((target, value)) = pending.pop_front() {
-        step_one(
-            modules,
+        Ok(Self {
+            memory: vec![false; start],
+            highs: vec![0; names.len()],
+            on: vec![false; names.len()],
+            names,
+            kinds,
+            outputs,
             inputs,
-            state,
-            &mut pending,
-            &mut total_high,
-            &mut total_low,
-            target,
-            value,
-        );
+            slots,
+            button,
+            pending: VecDeque::new(),
+        })
+    }
+
+    fn id(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    fn broadcaster(&self) -> usize {
+        self.outputs[self.button][0].0
+    }
+
+    fn send(&mut self, from: usize, high: bool) {
+        for &(to, slot) in self.outputs[from].iter() {
+            self.pending.push_back((Pulse { from, to, high }, slot));
+        }
     }
 
-    (total_low, total_high)
+    // Presses the button and delivers pulses until the circuit settles.
+    fn press(&mut self, observer: &mut impl Observer) {
+        self.send(self.button, false);
+        while let Some((pulse, slot)) = self.pending.pop_front() {
+            observer.observe(pulse);
+
+            let Pulse { to, high, .. } = pulse;
+            if self.memory[slot] != high {
+                self.memory[slot] = high;
+                if high {
+                    self.highs[to] += 1;
+                } else {
+                    self.highs[to] -= 1;
+                }
+            }
+
+            match self.kinds[to] {
+                Kind::Broadcaster => self.send(to, high),
+                Kind::FlipFlop => {
+                    if !high {
+                        self.on[to] = !self.on[to];
+                        self.send(to, self.on[to]);
+                    }
+                }
+                Kind::Conjunction => {
+                    let all_high = self.highs[to] == self.slots[to].len();
+                    self.send(to, !all_high);
+                }
+                Kind::Button | Kind::Output => (),
+            }
+        }
+    }
 }
 
-fn gcd(mut a: usize, mut b: usize) -> usize {
-    while b != 0 {
-        let t = b;
-        b = a % b;
-        a = t;
+// Circuits are equal when their modules are in the same states
+impl PartialEq for Circuit {
+    fn eq(&self, other: &Self) -> bool {
+        self.on == other.on && self.memory == other.memory
     }
-    a
 }
 
-fn lcm(a: usize, b: usize) -> usize {
-    a * b / gcd(a, b)
+impl Eq for Circuit {}
+
+// A part of the circuit which runs independently of the rest. The
+// broadcaster drives it through `start`, and it only affects the rest of the
+// circuit through `feed` sending to the final conjunction.
+struct SubCircuit {
+    start: usize,
+    feed: usize,
+    members: Vec<usize>,
 }
 
-fn main() -> common::Result<()> {
-    solve(
-        |input: &Lines<Module>| {
-            let (modules, inputs, mut state) = setup(&input.lines);
+// Splits the modules between the broadcaster and the conjunction which
+// drives `rx` into the sub-circuit behind each output of the broadcaster.
+fn decompose(circuit: &Circuit) -> common::Result<Vec<SubCircuit>> {
+    let rx = circuit.id("rx").context("no module sends to rx")?;
+    let last = match circuit.inputs[rx][..] {
+        [last] if circuit.kinds[last] == Kind::Conjunction => last,
+        _ => bail!("rx is not driven by a single conjunction"),
+    };
 
-            let mut total_low = 0;
-            let mut total_high = 0;
-            for _ in 0..1000 {
-                let (low, high) = step(&modules, &inputs, &mut state);
-                total_low += low;
-                total_high += high;
+    let broadcaster = circuit.broadcaster();
+    let mut owner: Vec<Option<usize>> = vec![None; circuit.names.len()];
+    let mut subcircuits: Vec<SubCircuit> = Vec::new();
+    for &(start, _) in circuit.outputs[broadcaster].iter() {
+        let index = subcircuits.len();
+        let mut members = Vec::new();
+        let mut feeds = Vec::new();
+        let mut pending = vec![start];
+        while let Some(module) = pending.pop() {
+            match owner[module] {
+                Some(i) if i == index => continue,
+                Some(i) => bail!(
+                    "the sub-circuits from {} and {} share {}",
+                    circuit.names[subcircuits[i].start],
+                    circuit.names[start],
+                    circuit.names[module],
+                ),
+                None => (),
             }
+            if module == rx || module == broadcaster {
+                bail!(
+                    "the sub-circuit from {} reaches {}",
+                    circuit.names[start],
+                    circuit.names[module],
+                );
+            }
+            owner[module] = Some(index);
+            members.push(module);
 
-            total_low * total_high
-        },
-        |input: &Lines<Module>| {
-            let (modules, inputs, mut state) = setup(&input.lines);
-
-            let factors = &inputs[&inputs["rx"][0]];
-
-            println!("{factors:?}");
-
-            let mut loop_lengths = vec![None; factors.len()];
-            for i in 1.. {
-                let mut pending = VecDeque::new();
-                let mut total_low = 0;
-                let mut total_high = 0;
-
-                pending.push_back(("broadcaster", false));
-                while let Some((target, value)) = pending.pop_front() {
-                    step_one(
-                        &modules,
-                        &inputs,
-                        &mut state,
-                        &mut pending,
-                        &mut total_high,
-                        &mut total_low,
-                        target,
-                        value,
-                    );
-                    for (f, factor) in factors.iter().enumerate() {
-                        if state[factor] {
-                            loop_lengths[f] = Some(i);
-                        }
+            for &(next, _) in circuit.outputs[module].iter() {
+                if next == last {
+                    if !feeds.contains(&module) {
+                        feeds.push(module);
                     }
+                } else {
+                    pending.push(next);
                 }
+            }
+        }
 
-                if loop_lengths.iter().all(Option::is_some) {
-                    break;
+        let feed = match feeds[..] {
+            [feed] => feed,
+            _ => bail!(
+                "the sub-circuit from {} sends to {} from {} modules",
+                circuit.names[start],
+                circuit.names[last],
+                feeds.len(),
+            ),
+        };
+        subcircuits.push(SubCircuit {
+            start,
+            feed,
+            members,
+        });
+    }
+
+    // Nothing outside a sub-circuit may reach into it, apart from the
+    // broadcaster
+    for (index, subcircuit) in subcircuits.iter().enumerate() {
+        for &module in subcircuit.members.iter() {
+            for &input in circuit.inputs[module].iter() {
+                if input != broadcaster && owner[input] != Some(index) {
+                    bail!(
+                        "{} sends to {} from outside its sub-circuit",
+                        circuit.names[input],
+                        circuit.names[module],
+                    );
                 }
             }
+        }
+    }
+    for &input in circuit.inputs[last].iter() {
+        if owner[input].is_none() {
+            bail!(
+                "{} sends to {} from outside every sub-circuit",
+                circuit.names[input],
+                circuit.names[last],
+            );
+        }
+    }
+
+    Ok(subcircuits)
+}
 
-            let mut result = 1;
-            for l in loop_lengths.iter() {
-                result = lcm(result, l.unwrap());
+// Finds the presses on which a sub-circuit sends a high pulse to the final
+// conjunction by simulating it alone until its state repeats. Requires that
+// this happens exactly once in each repetition, and never before them.
+// Returns `(phase, period)`: the first such press, and the number of presses
+// until the next one.
+fn schedule(
+    circuit: &Circuit,
+    subcircuit: &SubCircuit,
+) -> common::Result<(usize, usize)> {
+    let name = |id: usize| circuit.names[id].clone();
+    let mut alone = vec![Module {
+        kind: Kind::Broadcaster,
+        name: name(circuit.broadcaster()),
+        outputs: vec![name(subcircuit.start)],
+    }];
+    for &member in subcircuit.members.iter() {
+        // Undeclared outputs are added again wherever they are sent to
+        let kind = circuit.kinds[member];
+        if kind == Kind::Output {
+            continue;
+        }
+        alone.push(Module {
+            kind,
+            name: name(member),
+            outputs: circuit.outputs[member]
+                .iter()
+                .map(|&(to, _)| name(to))
+                .collect(),
+        });
+    }
+
+    let start = Circuit::new(&alone)?;
+    let Cycle { tail, length } =
+        brent(&start, |circuit| circuit.press(&mut ()));
+
+    // The presses from state `tail` onwards repeat
+    let mut sub = start;
+    let feed = sub
+        .id(&name(subcircuit.feed))
+        .context("the sub-circuit doesn't contain its feed")?;
+    let mut watch = Watch::new(vec![feed]);
+    let mut fired = Vec::new();
+    for press in 1..=tail + length {
+        watch.clear();
+        sub.press(&mut watch);
+        if watch.fired[0] {
+            fired.push(press);
+        }
+    }
+
+    match fired[..] {
+        [press] if press > tail => Ok((press, length)),
+        _ => bail!(
+            "{} sends high on presses {:?} of a cycle from press {} of \
+             length {}",
+            name(subcircuit.feed),
+            fired,
+            tail + 1,
+            length,
+        ),
+    }
+}
+
+// The fewest presses before every sub-circuit sends a high pulse to the
+// final conjunction during the same press, which sends a low pulse to rx.
+fn first_low_rx(circuit: &Circuit) -> common::Result<usize> {
+    let schedules = decompose(circuit)?
+        .iter()
+        .map(|subcircuit| schedule(circuit, subcircuit))
+        .collect::<common::Result<Vec<_>>>()?;
+
+    let (x, period) = crt(schedules
+        .iter()
+        .map(|&(phase, period)| (phase as i64, period as i64)))
+    .context("the sub-circuits never fire together")?;
+
+    // Every sub-circuit must have started firing
+    let earliest = schedules.iter().map(|&(phase, _)| phase as i64).max();
+    let mut press = x;
+    while press < earliest.context("the broadcaster sends nowhere")? {
+        press += period;
+    }
+    Ok(press as usize)
+}
+
+struct Config {
+    // The modules wired together before any presses
+    circuit: Circuit,
+}
+
+impl Input for Config {
+    fn parse_reader<R: BufRead>(reader: R) -> common::Result<Self> {
+        let modules = Lines::<Module>::parse_reader(reader)?.lines;
+        Ok(Self {
+            circuit: Circuit::new(&modules)?,
+        })
+    }
+
+    // The module network, with the conjunctions which combine the counters
+    // highlighted
+    fn to_dot(&self) -> Option<String> {
+        let circuit = &self.circuit;
+
+        let mut builder = GraphBuilder::new();
+        for (name, kind) in circuit.names.iter().zip(circuit.kinds.iter()) {
+            builder.node(&format!("{}{name}", kind.prefix()));
+        }
+        for (from, outputs) in circuit.outputs.iter().enumerate() {
+            for &(to, _) in outputs.iter() {
+                builder.add_edge(from, to, ());
+            }
+        }
+        let graph = builder.directed();
+
+        let conjunctions = graph
+            .nodes()
+            .filter(|&id| circuit.kinds[id] == Kind::Conjunction);
+        let dot = graph.dot().labeled().highlight_nodes(conjunctions);
+        Some(dot.to_string())
+    }
+}
+
+fn main() -> common::Result<()> {
+    try_solve(
+        |input: &Config| {
+            let mut circuit = input.circuit.clone();
+            let mut counter = Counter::default();
+            for _ in 0..1000 {
+                circuit.press(&mut counter);
             }
 
-            result
+            Ok(counter.low * counter.high)
         },
+        |input| first_low_rx(&input.circuit),
     )
 }
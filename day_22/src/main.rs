@@ -1,51 +1,14 @@
 use std::str::FromStr;
 
-use common::{solve, Context as _, Lines};
-
-#[derive(Clone, Copy, Debug)]
-struct Vec3 {
-    v: [i32; 3],
-}
-
-impl FromStr for Vec3 {
-    type Err = common::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut pieces = s.split(',').map(str::parse);
-        Ok(Self {
-            v: [
-                pieces.next().context("expected x coordinate")??,
-                pieces.next().context("expected y coordinate")??,
-                pieces.next().context("expected z coordinate")??,
-            ],
-        })
-    }
-}
+use common::{
+    graph::{Graph, GraphBuilder},
+    solve, Context as _, Lines, Vec3,
+};
 
 #[derive(Clone, Debug)]
 struct Volume {
-    lower: Vec3,
-    upper: Vec3,
-}
-
-impl Volume {
-    fn support_point(&self, other: &Self) -> Option<Vec3> {
-        if self.lower.v[0] > other.upper.v[0]
-            || self.upper.v[0] < other.lower.v[0]
-            || self.lower.v[1] > other.upper.v[1]
-            || self.upper.v[1] < other.lower.v[1]
-        {
-            return None;
-        }
-
-        Some(Vec3 {
-            v: [
-                i32::max(self.lower.v[0], other.lower.v[0]),
-                i32::max(self.lower.v[1], other.lower.v[1]),
-                i32::max(self.lower.v[2], self.upper.v[2]),
-            ],
-        })
-    }
+    lower: Vec3<i32>,
+    upper: Vec3<i32>,
 }
 
 impl FromStr for Volume {
@@ -62,78 +25,111 @@ impl FromStr for Volume {
     }
 }
 
-fn settle(bricks: &mut Vec<Volume>) -> usize {
-    let mut fell = 0;
+// Drops every brick as far as it will go, tracking the highest brick over
+// each x-y position. Bricks are numbered in the order they land, so every
+// brick comes after the bricks it rests on. Each edge leads from a brick to
+// a brick resting directly on it.
+fn settle(bricks: &[Volume]) -> Graph<usize> {
+    let mut order = (0..bricks.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| bricks[i].lower.z);
+
+    let width = bricks.iter().map(|b| b.upper.x + 1).max().unwrap_or(0);
+    let depth = bricks.iter().map(|b| b.upper.y + 1).max().unwrap_or(0);
+    // The top of the highest brick over each position, and which brick
+    let mut heights = vec![(0, None); (width * depth) as usize];
+
+    let mut supports = GraphBuilder::new();
+    for id in 0..bricks.len() {
+        supports.node(&id);
+    }
+    for (id, &i) in order.iter().enumerate() {
+        let brick = &bricks[i];
+        let footprint = (brick.lower.y..=brick.upper.y).flat_map(|y| {
+            (brick.lower.x..=brick.upper.x)
+                .map(move |x| (y * width + x) as usize)
+        });
+
+        let base = footprint.clone().map(|p| heights[p].0).max().unwrap_or(0);
+        let mut below = footprint
+            .clone()
+            .filter_map(|p| match heights[p] {
+                (top, Some(below)) if top == base => Some(below),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        below.sort_unstable();
+        below.dedup();
+        for below in below {
+            supports.add_edge(below, id, ());
+        }
 
-    // Pre-bricks are sorted by lowest Z
-    bricks.sort_unstable_by_key(|b| b.lower.v[2]);
-    // Drop the lowest brick to the ground
-    if bricks[0].lower.v[2] != 1 {
-        fell += 1;
+        let top = base + brick.upper.z - brick.lower.z + 1;
+        for p in footprint {
+            heights[p] = (top, Some(id));
+        }
     }
-    bricks[0].upper.v[2] -= bricks[0].lower.v[2] - 1;
-    bricks[0].lower.v[2] = 1;
-    for i in 1..bricks.len() {
-        // Drop brick `i`
-        let mut supports = Vec::new();
-        let mut new_z = 1;
-
-        for j in (0..i).rev() {
-            // If `j` supports `i`, drop it and sort into post-bricks
-            // post-bricks are sorted by highest Z
-            if let Some(p) = bricks[j].support_point(&bricks[i]) {
-                if p.v[2] + 1 > new_z {
-                    supports.clear();
-                    new_z = p.v[2] + 1;
-                }
-                if p.v[2] + 1 == new_z {
-                    supports.push(j);
+
+    supports.directed()
+}
+
+// The immediate dominator of each brick, with the ground as the root: the
+// closest brick which every path from the ground to the brick passes
+// through, or `None` if that is only the ground. Removing a brick makes
+// exactly the bricks it dominates fall. Also returns the depth of each brick
+// in the dominator tree.
+fn dominators(supports: &Graph<usize>) -> (Vec<Option<usize>>, Vec<usize>) {
+    let resting_on = supports.reversed();
+    let mut parents = vec![None; supports.len()];
+    let mut depths = vec![0; supports.len()];
+
+    // Bricks come after everything below them, so each brick's supports
+    // already have their dominators
+    for brick in supports.nodes() {
+        let mut below = resting_on.neighbors(brick);
+        let mut parent = below.next();
+        for other in below {
+            // Find the closest common dominator of the two
+            let mut a = parent;
+            let mut b = Some(other);
+            while a != b {
+                let depth = |n: Option<usize>| n.map_or(0, |n| depths[n]);
+                if depth(a) >= depth(b) {
+                    a = a.and_then(|a| parents[a]);
+                } else {
+                    b = b.and_then(|b| parents[b]);
                 }
             }
+            parent = a;
         }
 
-        let d = bricks[i].lower.v[2] - new_z;
-        if d > 0 {
-            bricks[i].lower.v[2] -= d;
-            bricks[i].upper.v[2] -= d;
-            bricks[0..=i].sort_unstable_by_key(|b| b.upper.v[2]);
-
-            fell += 1;
-        }
+        parents[brick] = parent;
+        depths[brick] = parent.map_or(1, |p| depths[p] + 1);
     }
 
-    fell
+    (parents, depths)
 }
 
 fn main() -> common::Result<()> {
     solve(
         |input: &Lines<Volume>| {
-            let mut bricks = input.lines.clone();
-            settle(&mut bricks);
-
-            let mut removable = 0;
-            for i in 0..bricks.len() {
-                let mut b = bricks.clone();
-                b.swap_remove(i);
-                if settle(&mut b) == 0 {
-                    removable += 1;
-                }
-            }
-
-            removable
+            let supports = settle(&input.lines);
+            let resting_on = supports.reversed();
+
+            // A brick can go if everything on it rests on something else too
+            supports
+                .nodes()
+                .filter(|&brick| {
+                    supports
+                        .neighbors(brick)
+                        .all(|above| resting_on.degree(above) > 1)
+                })
+                .count()
         },
         |input| {
-            let mut bricks = input.lines.clone();
-            settle(&mut bricks);
-
-            let mut settled = 0;
-            for i in 0..bricks.len() {
-                let mut b = bricks.clone();
-                b.swap_remove(i);
-                settled += settle(&mut b);
-            }
+            let (_, depths) = dominators(&settle(&input.lines));
 
-            settled
+            // Each brick falls when any of the bricks dominating it is removed
+            depths.iter().map(|depth| depth - 1).sum::<usize>()
         },
     )
 }
@@ -1,6 +1,15 @@
-use std::collections::{HashMap, HashSet, VecDeque};
-
-use common::{bail, solve, Grid};
+use std::{
+    collections::VecDeque,
+    io::BufRead,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use common::{
+    bail,
+    graph::{Graph, GraphBuilder},
+    try_solve, Context, Grid, Input,
+};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum Tile {
@@ -60,57 +69,8 @@ impl Direction {
     }
 }
 
-#[derive(Debug)]
-struct Edge {
-    dest: usize,
-    weight: usize,
-}
-
-#[derive(Debug)]
-struct Node {
-    edges: Vec<Edge>,
-}
-
-impl Node {
-    fn new() -> Self {
-        Self { edges: Vec::new() }
-    }
-}
-
-#[derive(Debug)]
-struct Graph {
-    nodes: Vec<Node>,
-}
-
-impl Graph {
-    fn new() -> Self {
-        Self { nodes: Vec::new() }
-    }
-
-    fn add_node(&mut self) -> usize {
-        let result = self.nodes.len();
-        self.nodes.push(Node::new());
-        result
-    }
-
-    fn add_edge(&mut self, src: usize, dest: usize, weight: usize) {
-        self.nodes[src].edges.push(Edge { dest, weight })
-    }
-
-    fn add_reverse_edges(&mut self) {
-        for n in 0..self.nodes.len() {
-            for e in 0..self.nodes[n].edges.len() {
-                let edge = &self.nodes[n].edges[e];
-                let dest = edge.dest;
-                let weight = edge.weight;
-
-                if self.nodes[dest].edges.iter().all(|e| e.dest != n) {
-                    self.nodes[dest].edges.push(Edge { dest: n, weight });
-                }
-            }
-        }
-    }
-}
+// Junctions labeled by position, with edges weighted by corridor length
+type Trails = Graph<(usize, usize), usize>;
 
 fn trace_corridor(
     grid: &Grid<Tile>,
@@ -149,31 +109,31 @@ fn trace_corridor(
     (x, y, len)
 }
 
-fn to_graph(grid: &Grid<Tile>) -> Graph {
-    let mut graph = Graph::new();
-    let start_node = graph.add_node();
-    let end_node = graph.add_node();
+// The ids of the start and end junctions in the trail graph
+const START: usize = 0;
+const EXIT: usize = 1;
 
-    let mut joints = HashMap::new();
-
-    let mut frontier = VecDeque::new();
+fn to_graph(grid: &Grid<Tile>) -> Trails {
+    let mut graph = GraphBuilder::new();
 
-    let start_x = (0..grid.width())
-        .find(|x| *grid.get(*x, 0).unwrap() == Tile::Path)
-        .unwrap();
+    let find_path = |y| {
+        (0..grid.width())
+            .find(|x| *grid.get(*x, y).unwrap() == Tile::Path)
+            .unwrap()
+    };
+    let start_x = find_path(0);
+    let start_node = graph.node(&(start_x, 0));
+    graph.node(&(find_path(grid.height() - 1), grid.height() - 1));
 
+    let mut frontier = VecDeque::new();
     frontier.push_back((start_x, 0, start_node));
 
     while let Some((x, y, src)) = frontier.pop_front() {
         let (end_x, end_y, mut length) = trace_corridor(grid, x, y);
 
-        let dest = if end_y == grid.height() - 1 {
-            end_node
-        } else if let Some(dest) = joints.get(&(end_x, end_y)) {
-            *dest
-        } else {
-            let dest = graph.add_node();
-            joints.insert((end_x, end_y), dest);
+        let is_new = graph.id(&(end_x, end_y)).is_none();
+        let dest = graph.node(&(end_x, end_y));
+        if is_new {
             for d in Direction::ALL {
                 let (nx, ny) = d.add(end_x, end_y).unwrap();
                 if *grid.get(nx, ny).unwrap() == Tile::slope(d) {
@@ -181,8 +141,7 @@ fn to_graph(grid: &Grid<Tile>) -> Graph {
                     frontier.push_back((cx, cy, dest));
                 }
             }
-            dest
-        };
+        }
 
         if src != start_node {
             length += 2;
@@ -191,85 +150,182 @@ fn to_graph(grid: &Grid<Tile>) -> Graph {
         graph.add_edge(src, dest, length);
     }
 
-    graph
+    graph.directed()
 }
 
-fn toposort(graph: &Graph, node: usize) -> Vec<usize> {
-    let mut result = Vec::new();
-    let mut visited = vec![false; graph.nodes.len()];
-    toposort_at(graph, node, &mut visited, &mut result);
-    result.reverse();
-    result
+// A partial hike: the junction reached, the junctions visited so far as a
+// bitmask, the distance walked and the sum of `longest` over the junctions
+// which are still unvisited.
+#[derive(Clone, Copy)]
+struct Prefix {
+    node: usize,
+    visited: u64,
+    distance: usize,
+    remaining: usize,
 }
 
-fn toposort_at(
-    graph: &Graph,
-    node: usize,
-    visited: &mut Vec<bool>,
-    result: &mut Vec<usize>,
-) {
-    if !visited[node] {
-        visited[node] = true;
-        for e in graph.nodes[node].edges.iter() {
-            toposort_at(graph, e.dest, visited, result);
-        }
-        result.push(node);
-    }
+// The undirected trail graph packed for an exhaustive search over simple
+// paths, with each junction as one bit of a `u64`.
+struct Hike {
+    edges: Vec<Vec<(usize, usize)>>,
+    // The longest edge leaving each junction, which bounds how far a path
+    // can still go. The exit never has a path leave it.
+    longest: Vec<usize>,
+    // The only junction next to the exit, with the distance to the exit. A
+    // path which reaches it must go straight to the exit or it is stuck.
+    last: Option<(usize, usize)>,
 }
 
-fn longest_path_dag(graph: &Graph) -> usize {
-    let mut max_distance = HashMap::new();
-    let order = toposort(graph, 0);
+impl Hike {
+    fn new(graph: &Trails) -> common::Result<Self> {
+        if graph.len() > 64 {
+            bail!("too many junctions: {}", graph.len());
+        }
+
+        let edges = graph
+            .nodes()
+            .map(|node| {
+                graph.edges_from(node).map(|(next, &w)| (next, w)).collect()
+            })
+            .collect::<Vec<Vec<_>>>();
+
+        let mut longest = edges
+            .iter()
+            .map(|edges| edges.iter().map(|&(_, w)| w).max().unwrap_or(0))
+            .collect::<Vec<_>>();
+        longest[EXIT] = 0;
+
+        let last = match edges[EXIT][..] {
+            [(last, w)] if last != START => Some((last, w)),
+            _ => None,
+        };
+
+        Ok(Self {
+            edges,
+            longest,
+            last,
+        })
+    }
 
-    max_distance.insert(0, 0);
-    for &node in order.iter() {
-        let d = max_distance[&node];
-        for e in graph.nodes[node].edges.iter() {
-            let x = max_distance.entry(e.dest).or_insert(d + e.weight);
-            *x = usize::max(*x, d + e.weight);
+    fn start(&self) -> Prefix {
+        Prefix {
+            node: START,
+            visited: 1 << START,
+            distance: 0,
+            remaining: self.longest.iter().sum::<usize>() - self.longest[START],
         }
     }
 
-    max_distance[&1]
-}
+    // The prefixes one step further on, recording any which finish.
+    fn extend<'a>(
+        &'a self,
+        prefix: Prefix,
+        best: &'a AtomicUsize,
+    ) -> impl Iterator<Item = Prefix> + 'a {
+        self.edges[prefix.node]
+            .iter()
+            .filter(move |&&(next, _)| prefix.visited & 1 << next == 0)
+            .map(move |&(next, w)| Prefix {
+                node: next,
+                visited: prefix.visited | 1 << next,
+                distance: prefix.distance + w,
+                remaining: prefix.remaining - self.longest[next],
+            })
+            .filter(move |prefix| {
+                if prefix.node == EXIT {
+                    best.fetch_max(prefix.distance, Ordering::Relaxed);
+                }
+                prefix.node != EXIT
+            })
+    }
 
-fn longest_path(graph: &Graph) -> usize {
-    let mut visited = HashSet::new();
-    longest_path_sub(graph, 0, &mut visited).unwrap()
-}
+    fn search(&self, prefix: Prefix, best: &AtomicUsize) {
+        let bound =
+            prefix.distance + self.longest[prefix.node] + prefix.remaining;
+        if bound <= best.load(Ordering::Relaxed) {
+            return;
+        }
 
-fn longest_path_sub(
-    graph: &Graph,
-    node: usize,
-    visited: &mut HashSet<usize>,
-) -> Option<usize> {
-    if node == 1 {
-        Some(0)
-    } else if !visited.contains(&node) {
-        visited.insert(node);
-
-        let mut max = None;
-        for e in graph.nodes[node].edges.iter() {
-            if let Some(d) = longest_path_sub(graph, e.dest, visited) {
-                max = Some(usize::max(max.unwrap_or(0), e.weight + d));
+        if let Some((last, w)) = self.last {
+            if prefix.node == last {
+                best.fetch_max(prefix.distance + w, Ordering::Relaxed);
+                return;
             }
         }
 
-        visited.remove(&node);
+        for next in self.extend(prefix, best) {
+            self.search(next, best);
+        }
+    }
+
+    // Splits the search into independent prefixes and shares them between
+    // threads, which all prune against the best hike found so far.
+    fn longest(&self) -> usize {
+        let best = AtomicUsize::new(0);
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
 
-        max
-    } else {
-        None
+        let mut prefixes = vec![self.start()];
+        for _ in 0..self.edges.len() {
+            if prefixes.len() >= 16 * threads {
+                break;
+            }
+            prefixes = prefixes
+                .into_iter()
+                .flat_map(|prefix| self.extend(prefix, &best))
+                .collect();
+        }
+
+        let next = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(|| {
+                    while let Some(&prefix) =
+                        prefixes.get(next.fetch_add(1, Ordering::Relaxed))
+                    {
+                        self.search(prefix, &best);
+                    }
+                });
+            }
+        });
+
+        best.into_inner()
+    }
+}
+
+struct Map {
+    grid: Grid<Tile>,
+}
+
+impl Input for Map {
+    fn parse_reader<R: BufRead>(reader: R) -> common::Result<Self> {
+        Ok(Self {
+            grid: Grid::parse_reader(reader)?,
+        })
+    }
+
+    // The junctions and the lengths of the trails between them
+    fn to_dot(&self) -> Option<String> {
+        let graph = to_graph(&self.grid);
+        let dot = graph
+            .dot()
+            .label_with(|(x, y)| format!("{x},{y}"))
+            .weighted()
+            .highlight_nodes([START, EXIT])
+            .to_string();
+        Some(dot)
     }
 }
 
 fn main() -> common::Result<()> {
-    solve(
-        |input: &Grid<Tile>| longest_path_dag(&to_graph(input)),
+    try_solve(
+        |input: &Map| {
+            let graph = to_graph(&input.grid);
+            graph.longest_paths(START)?[EXIT]
+                .context("the exit can't be reached")
+        },
         |input| {
-            let mut graph = to_graph(input);
-            graph.add_reverse_edges();
-            longest_path(&graph)
+            let graph = to_graph(&input.grid).to_undirected();
+            Ok(Hike::new(&graph)?.longest())
         },
     )
 }
@@ -1,54 +1,15 @@
-use std::{
-    ops::{Add, Neg},
-    str::FromStr,
-};
-
-use common::{solve, Context, Lines};
-
-#[derive(Clone, Copy)]
-struct Vec3 {
-    x: f64,
-    y: f64,
-    z: f64,
-}
-
-impl Vec3 {
-    fn cross(&self, other: Self) -> Self {
-        Self {
-            x: self.y * other.z - self.z * other.y,
-            y: self.z * other.x - self.x * other.z,
-            z: self.x * other.y - self.y * other.x,
-        }
-    }
-}
-
-impl Neg for Vec3 {
-    type Output = Self;
-
-    fn neg(self) -> Self::Output {
-        Self {
-            x: -self.x,
-            y: -self.y,
-            z: -self.z,
-        }
-    }
-}
+use std::str::FromStr;
 
-impl Add for Vec3 {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-            z: self.z + rhs.z,
-        }
-    }
-}
+use common::{
+    bail,
+    linalg::{Matrix, Solution},
+    num::{BigInt, BigRational},
+    try_solve, Context, Lines, Vec3,
+};
 
 struct Hailstone {
-    p: Vec3,
-    v: Vec3,
+    p: Vec3<i64>,
+    v: Vec3<i64>,
 }
 
 impl FromStr for Hailstone {
@@ -58,42 +19,111 @@ impl FromStr for Hailstone {
         let (p, v) = s
             .split_once(" @ ")
             .context("expected position and velocity")?;
-        let mut p_pieces = p.split(", ").map(str::trim).map(str::parse);
-        let mut v_pieces = v.split(", ").map(str::trim).map(str::parse);
 
         Ok(Self {
-            p: Vec3 {
-                x: p_pieces.next().context("expected X position")??,
-                y: p_pieces.next().context("expected Y position")??,
-                z: p_pieces.next().context("expected Z position")??,
-            },
-            v: Vec3 {
-                x: v_pieces.next().context("expected X velocity")??,
-                y: v_pieces.next().context("expected Y velocity")??,
-                z: v_pieces.next().context("expected Z velocity")??,
-            },
+            p: p.parse().context("invalid position")?,
+            v: v.parse().context("invalid velocity")?,
         })
     }
 }
 
 impl Hailstone {
     fn intersection_2d(&self, other: &Self) -> Option<(f64, f64)> {
-        let k = other.v.y - self.v.y * other.v.x / self.v.x;
-        let t1 = (self.p.y + (other.p.x - self.p.x) * self.v.y / self.v.x
-            - other.p.y)
-            / k;
-        let t0 = (other.v.x * t1 + (other.p.x - self.p.x)) / self.v.x;
+        let (sp, sv) = (self.p.map(|x| x as f64), self.v.map(|x| x as f64));
+        let (op, ov) = (other.p.map(|x| x as f64), other.v.map(|x| x as f64));
+
+        let k = ov.y - sv.y * ov.x / sv.x;
+        let t1 = (sp.y + (op.x - sp.x) * sv.y / sv.x - op.y) / k;
+        let t0 = (ov.x * t1 + (op.x - sp.x)) / sv.x;
 
         if t0 >= 0.0 && t1 >= 0.0 {
-            Some((other.p.x + other.v.x * t1, other.p.y + other.v.y * t1))
+            Some((op.x + ov.x * t1, op.y + ov.y * t1))
         } else {
             None
         }
     }
+
+    fn wide(&self) -> (Vec3<i128>, Vec3<i128>) {
+        (self.p.map(i128::from), self.v.map(i128::from))
+    }
+
+    // Whether a rock thrown from `p` with velocity `v` collides with this
+    // hailstone at some non-negative integer time.
+    fn hits(&self, p: Vec3<i128>, v: Vec3<i128>) -> bool {
+        let (hp, hv) = self.wide();
+        let dp = hp - p;
+        let dv = v - hv;
+
+        let mut time = None;
+        for (d, s) in [(dp.x, dv.x), (dp.y, dv.y), (dp.z, dv.z)] {
+            if s == 0 {
+                if d != 0 {
+                    return false;
+                }
+            } else if d % s != 0
+                || d / s < 0
+                || time.is_some_and(|t| t != d / s)
+            {
+                return false;
+            } else {
+                time = Some(d / s);
+            }
+        }
+
+        true
+    }
+}
+
+fn exact(value: i128) -> BigRational {
+    BigInt::from(value).into()
+}
+
+fn cross_matrix(v: Vec3<i128>) -> [[i128; 3]; 3] {
+    [[0, -v.z, v.y], [v.z, 0, -v.x], [-v.y, v.x, 0]]
+}
+
+// Solves for the position and velocity of a rock which collides with all three
+// hailstones. For each hailstone `i`, `(p - p_i) x (v - v_i) = 0`. Subtracting
+// the equation for `a` from those for `b` and `c` cancels the nonlinear
+// `p x v` term and leaves six linear equations. Returns `None` if the
+// hailstones do not determine a unique integer trajectory.
+fn rock_from(
+    a: &Hailstone,
+    b: &Hailstone,
+    c: &Hailstone,
+) -> Option<(Vec3<i128>, Vec3<i128>)> {
+    let (pa, va) = a.wide();
+
+    let mut elements = Vec::with_capacity(36);
+    let mut rhs = Vec::with_capacity(6);
+    for other in [b, c] {
+        let (po, vo) = other.wide();
+        let m = cross_matrix(va - vo);
+        let n = cross_matrix(po - pa);
+        for row in 0..3 {
+            elements
+                .extend(m[row].iter().chain(n[row].iter()).map(|&x| exact(x)));
+        }
+
+        let r = po.cross(vo) - pa.cross(va);
+        rhs.extend(r.to_array().map(exact));
+    }
+
+    let Solution::Unique(solution) =
+        Matrix::from_elements(6, 6, elements).solve(&rhs)
+    else {
+        return None;
+    };
+    let s = solution
+        .iter()
+        .map(|x| x.to_integer()?.to_i128())
+        .collect::<Option<Vec<_>>>()?;
+
+    Some((Vec3::new(s[0], s[1], s[2]), Vec3::new(s[3], s[4], s[5])))
 }
 
 fn main() -> common::Result<()> {
-    solve(
+    try_solve(
         |input: &Lines<Hailstone>| {
             const MIN: f64 = 200000000000000.0;
             const MAX: f64 = 400000000000000.0;
@@ -112,48 +142,25 @@ fn main() -> common::Result<()> {
                 }
             }
 
-            total
+            Ok(total)
         },
         |input| {
-            // This impl taken from a reddit comment. I did not enjoy this one.
-            use nalgebra::{Matrix3, Matrix6, Vector6};
-
-            let s0 = 0;
-            let s1 = 1;
-            let s2 = 2;
-
-            let r0 = -input.lines[s0].p.cross(input.lines[s0].v)
-                + input.lines[s1].p.cross(input.lines[s1].v);
-            let r1 = -input.lines[s0].p.cross(input.lines[s0].v)
-                + input.lines[s2].p.cross(input.lines[s2].v);
-            let rhs = Vector6::new(r0.x, r0.y, r0.z, r1.x, r1.y, r1.z);
-
-            fn cross_matrix(v: Vec3) -> Matrix3<f64> {
-                Matrix3::new(0.0, -v.z, v.y, v.z, 0.0, -v.x, -v.y, v.x, 0.0)
+            let hailstones = &input.lines;
+            let n = hailstones.len();
+
+            let (p, v) = (0..n)
+                .flat_map(|a| (a + 1..n).map(move |b| (a, b)))
+                .flat_map(|(a, b)| (b + 1..n).map(move |c| (a, b, c)))
+                .find_map(|(a, b, c)| {
+                    rock_from(&hailstones[a], &hailstones[b], &hailstones[c])
+                })
+                .context("no three hailstones determine a rock trajectory")?;
+
+            if !hailstones.iter().all(|h| h.hits(p, v)) {
+                bail!("rock trajectory does not hit every hailstone");
             }
 
-            let m0 = cross_matrix(input.lines[s0].v)
-                - cross_matrix(input.lines[s1].v);
-            let m1 = cross_matrix(input.lines[s0].v)
-                - cross_matrix(input.lines[s2].v);
-            let m2 = -cross_matrix(input.lines[s0].p)
-                + cross_matrix(input.lines[s1].p);
-            let m3 = -cross_matrix(input.lines[s0].p)
-                + cross_matrix(input.lines[s2].p);
-            let m = Matrix6::new(
-                m0.m11, m0.m12, m0.m13, m2.m11, m2.m12, m2.m13, m0.m21, m0.m22,
-                m0.m23, m2.m21, m2.m22, m2.m23, m0.m31, m0.m32, m0.m33, m2.m31,
-                m2.m32, m2.m33, m1.m11, m1.m12, m1.m13, m3.m11, m3.m12, m3.m13,
-                m1.m21, m1.m22, m1.m23, m3.m21, m3.m22, m3.m23, m1.m31, m1.m32,
-                m1.m33, m3.m31, m3.m32, m3.m33,
-            );
-            let result = m.try_inverse().unwrap() * rhs;
-
-            let x = result[0].round() as isize;
-            let y = result[1].round() as isize;
-            let z = result[2].round() as isize;
-
-            x + y + z
+            Ok(p.x + p.y + p.z)
         },
     )
 }
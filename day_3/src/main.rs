@@ -62,8 +62,8 @@ fn main() -> common::Result<()> {
                     }
                 }
 
-                if is_adjacent && n.is_some() {
-                    total += n.unwrap();
+                if let (true, Some(part)) = (is_adjacent, n) {
+                    total += part;
                 }
             }
 
@@ -1,12 +1,6 @@
 use std::{collections::HashMap, io::prelude::BufRead, str::FromStr};
 
-use common::{solve, Context, Input};
-
-#[derive(Debug)]
-struct Run {
-    start: usize,
-    len: usize,
-}
+use common::{bail, interval::IntervalMap, try_solve, Context, Input};
 
 #[derive(Debug)]
 struct Range {
@@ -15,60 +9,6 @@ struct Range {
     len: usize,
 }
 
-impl Range {
-    fn map(&self, input: usize) -> Option<usize> {
-        if input >= self.src_start && input < self.src_start + self.len {
-            Some(input - self.src_start + self.dest_start)
-        } else {
-            None
-        }
-    }
-
-    fn map_run(
-        &self,
-        run: Run,
-        unmapped: &mut Vec<Run>,
-        mapped: &mut Vec<Run>,
-    ) {
-        let map_end = self.src_start + self.len;
-        let run_end = run.start + run.len;
-
-        if self.src_start <= run.start && run.start < map_end {
-            // run interrupts map
-            mapped.push(Run {
-                start: run.start - self.src_start + self.dest_start,
-                len: usize::min(run.len, map_end - run.start),
-            });
-            if run_end > map_end {
-                unmapped.push(Run {
-                    start: map_end,
-                    len: run_end - map_end,
-                });
-            }
-        } else if run.start <= self.src_start && self.src_start < run_end {
-            // map interrupts run
-            if run.start < self.src_start {
-                unmapped.push(Run {
-                    start: run.start,
-                    len: self.src_start - run.start,
-                });
-            }
-            mapped.push(Run {
-                start: self.dest_start,
-                len: usize::min(self.len, run_end - self.src_start),
-            });
-            if map_end < run_end {
-                unmapped.push(Run {
-                    start: map_end,
-                    len: run_end - map_end,
-                });
-            }
-        } else {
-            unmapped.push(run);
-        }
-    }
-}
-
 impl FromStr for Range {
     type Err = common::Error;
 
@@ -87,30 +27,16 @@ impl FromStr for Range {
 
 struct Map {
     dest: String,
-    ranges: Vec<Range>,
+    map: IntervalMap<usize>,
 }
 
 impl Map {
-    fn map(&self, value: usize) -> usize {
-        self.ranges
-            .iter()
-            .filter_map(|r| r.map(value))
-            .next()
-            .unwrap_or(value)
-    }
-
-    fn map_run(&self, run: Run, mapped: &mut Vec<Run>) {
-        let mut unmapped = vec![run];
-
-        for range in self.ranges.iter() {
-            let mut new_unmapped = Vec::new();
-            for run in unmapped.drain(..) {
-                range.map_run(run, &mut new_unmapped, mapped);
-            }
-            core::mem::swap(&mut unmapped, &mut new_unmapped);
+    // Returns the map which applies `self` and then `next`.
+    fn then(&self, next: &Self) -> Self {
+        Self {
+            dest: next.dest.clone(),
+            map: self.map.then(&next.map),
         }
-
-        mapped.extend(unmapped);
     }
 }
 
@@ -141,7 +67,7 @@ impl Input for Almanac {
                 .context("expected map line to end with ' map:'")?
                 .split_once("-to-")
                 .context("expected '-to-' separator")?;
-            let mut ranges = Vec::new();
+            let mut map = IntervalMap::new();
 
             for range_line in lines.by_ref() {
                 let range_line = range_line?;
@@ -150,14 +76,18 @@ impl Input for Almanac {
                     break;
                 }
 
-                ranges.push(range_line.parse()?);
+                let range: Range = range_line.parse()?;
+                map.insert(
+                    range.src_start..range.src_start + range.len,
+                    range.dest_start,
+                );
             }
 
             maps.insert(
                 source.to_string(),
                 Map {
                     dest: dest.to_string(),
-                    ranges,
+                    map,
                 },
             );
         }
@@ -166,42 +96,49 @@ impl Input for Almanac {
     }
 }
 
-fn main() -> common::Result<()> {
-    solve(
-        |input: &Almanac| {
-            let mut values = input.seeds.clone();
-            let mut name = "seed";
+impl Almanac {
+    // Folds the chain of maps from `source` to `dest` into a single map.
+    fn compose(&self, source: &str, dest: &str) -> common::Result<Map> {
+        let mut result = Map {
+            dest: source.to_string(),
+            map: IntervalMap::new(),
+        };
+
+        for _ in 0..=self.maps.len() {
+            if result.dest == dest {
+                return Ok(result);
+            }
+            let next = self.maps.get(&result.dest).with_context(|| {
+                format!("no map from '{}' toward '{dest}'", result.dest)
+            })?;
+            result = result.then(next);
+        }
 
-            while name != "location" {
-                let map = input.maps.get(name).unwrap();
-                values.iter_mut().for_each(|value| *value = map.map(*value));
+        bail!("maps from '{source}' loop without reaching '{dest}'")
+    }
+}
 
-                name = &map.dest;
-            }
+fn main() -> common::Result<()> {
+    try_solve(
+        |input: &Almanac| {
+            let map = input.compose("seed", "location")?.map;
 
-            *values.iter().min().unwrap()
-        },
-        |input| {
-            let mut runs = input
+            input
                 .seeds
                 .iter()
-                .step_by(2)
-                .zip(input.seeds.iter().skip(1).step_by(2))
-                .map(|(&start, &len)| Run { start, len })
-                .collect::<Vec<_>>();
-            let mut name = "seed";
-
-            while name != "location" {
-                let map = input.maps.get(name).unwrap();
-                let mut new_runs = Vec::new();
-                runs.drain(..)
-                    .for_each(|run| map.map_run(run, &mut new_runs));
-                core::mem::swap(&mut runs, &mut new_runs);
-
-                name = &map.dest;
-            }
+                .map(|&seed| map.get(seed))
+                .min()
+                .context("expected seeds")
+        },
+        |input| {
+            let map = input.compose("seed", "location")?.map;
 
-            runs.iter().min_by_key(|x| x.start).unwrap().start
+            input
+                .seeds
+                .chunks_exact(2)
+                .filter_map(|pair| map.min_over(pair[0]..pair[0] + pair[1]))
+                .min()
+                .context("expected non-empty seed ranges")
         },
     )
 }
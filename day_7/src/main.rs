@@ -30,7 +30,7 @@ impl HandKind {
                 }
             }
             3 => {
-                if counts.iter().any(|x| *x == 2) {
+                if counts.contains(&2) {
                     Self::FullHouse
                 } else {
                     Self::ThreeOfAKind
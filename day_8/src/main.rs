@@ -1,47 +1,144 @@
-use std::{collections::HashMap, io::prelude::BufRead};
+use std::{collections::BTreeSet, io::prelude::BufRead};
 
-use common::{solve, Context, Input};
+use common::{
+    cycle::Cycle,
+    graph::{Graph, GraphBuilder},
+    math::{checked_lcm, crt},
+    try_solve, Context, Input,
+};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 enum Direction {
     Left,
     Right,
 }
 
-struct Node {
-    left: String,
-    right: String,
+struct Map {
+    steps: Vec<Direction>,
+    nodes: Graph<String, Direction>,
 }
 
-impl Node {
-    fn get(&self, d: Direction) -> &str {
-        match d {
-            Direction::Left => &self.left,
-            Direction::Right => &self.right,
+impl Map {
+    fn next(&self, node: usize, d: Direction) -> usize {
+        self.nodes
+            .edges_from(node)
+            .find(|&(_, &side)| side == d)
+            .unwrap()
+            .0
+    }
+
+    fn is_z(&self, node: usize) -> bool {
+        self.nodes.label(node).ends_with('Z')
+    }
+
+    // Walks from `start` until the same node is reached at the same point in
+    // the steps, which must happen within `nodes * steps` steps.
+    fn trajectory(&self, start: usize) -> Trajectory {
+        let len = self.steps.len();
+        let mut seen = vec![usize::MAX; self.nodes.len() * len];
+        let mut hits = Vec::new();
+        let mut current = start;
+        let mut step = 0;
+
+        loop {
+            let state = current * len + step % len;
+            if seen[state] != usize::MAX {
+                let tail = seen[state];
+                let (tail_hits, loop_hits) =
+                    hits.into_iter().partition(|&hit| hit < tail);
+                return Trajectory {
+                    cycle: Cycle {
+                        tail,
+                        length: step - tail,
+                    },
+                    tail_hits,
+                    loop_hits,
+                };
+            }
+            seen[state] = step;
+
+            if step > 0 && self.is_z(current) {
+                hits.push(step);
+            }
+            current = self.next(current, self.steps[step % len]);
+            step += 1;
         }
     }
 }
 
-struct Map {
-    steps: Vec<Direction>,
-    nodes: HashMap<String, Node>,
+// The steps on which a ghost is at a node ending in Z. After `cycle.tail`
+// steps the ghost loops, so each of `loop_hits` recurs every `cycle.length`
+// steps.
+struct Trajectory {
+    cycle: Cycle,
+    tail_hits: Vec<usize>,
+    loop_hits: Vec<usize>,
 }
 
-impl Map {
-    fn find_z(&self, start: &str) -> usize {
-        let mut current = start;
-        let mut steps = 0;
+impl Trajectory {
+    fn hits(&self, step: usize) -> bool {
+        if step < self.cycle.tail {
+            self.tail_hits.contains(&step)
+        } else {
+            self.loop_hits.contains(&self.cycle.reduce(step))
+        }
+    }
+}
 
-        loop {
-            current =
-                self.nodes[current].get(self.steps[steps % self.steps.len()]);
-            steps += 1;
+// The first step on which every ghost is at a node ending in Z.
+fn earliest_together(ghosts: &[Trajectory]) -> common::Result<usize> {
+    // Any step before every ghost is looping is in the tail of the last
+    // ghost to start looping
+    let last = ghosts
+        .iter()
+        .max_by_key(|ghost| ghost.cycle.tail)
+        .context("expected at least one ghost")?;
+    if let Some(&step) = last
+        .tail_hits
+        .iter()
+        .find(|&&step| ghosts.iter().all(|ghost| ghost.hits(step)))
+    {
+        return Ok(step);
+    }
 
-            if current.ends_with('Z') {
-                break steps;
+    // Otherwise pick one hit from each loop and solve for the steps which
+    // land on all of them. These are kept as residues modulo the lcm of the
+    // loop lengths so far, so there are never more residues than that.
+    let mut residues = BTreeSet::from([0]);
+    let mut modulus = 1;
+    for ghost in ghosts.iter() {
+        let length = ghost.cycle.length as i128;
+        let combined = checked_lcm(modulus, length)
+            .context("the combined loop length overflowed")?;
+
+        // Both moduli divide `combined`, so `crt` only fails when the two
+        // congruences disagree
+        let mut next = BTreeSet::new();
+        for &x in residues.iter() {
+            for &hit in ghost.loop_hits.iter() {
+                if let Some((y, _)) = crt([(x, modulus), (hit as i128, length)])
+                {
+                    next.insert(y);
+                }
             }
         }
+        residues = next;
+        modulus = combined;
     }
+
+    let start = last.cycle.tail.max(1) as i128;
+    let step = residues
+        .into_iter()
+        .map(|x| {
+            if x >= start {
+                x
+            } else {
+                x + (start - x + modulus - 1) / modulus * modulus
+            }
+        })
+        .min()
+        .context("the ghosts are never all at Z nodes together")?;
+    Ok(step.try_into()?)
 }
 
 impl Input for Map {
@@ -63,7 +160,7 @@ impl Input for Map {
 
         lines.next().context("expected newline")??;
 
-        let mut nodes = HashMap::new();
+        let mut nodes = GraphBuilder::new();
 
         for line in lines {
             let line = line?;
@@ -71,52 +168,33 @@ impl Input for Map {
                 line.split_once(" = ").context("expected name and nodes")?;
             let (left, right) =
                 rest.split_once(", ").context("expected left and right")?;
-            nodes.insert(
-                name.to_string(),
-                Node {
-                    left: left
-                        .strip_prefix('(')
-                        .context("missing open paren")?
-                        .to_string(),
-                    right: right
-                        .strip_suffix(')')
-                        .context("missing close paren")?
-                        .to_string(),
-                },
-            );
+            let left = left.strip_prefix('(').context("missing open paren")?;
+            let right =
+                right.strip_suffix(')').context("missing close paren")?;
+
+            nodes.add_labeled_edge(name, left, Direction::Left);
+            nodes.add_labeled_edge(name, right, Direction::Right);
         }
+        let nodes = nodes.directed();
 
         Ok(Self { steps, nodes })
     }
 }
 
-fn gcd(mut a: usize, mut b: usize) -> usize {
-    while b != 0 {
-        let t = b;
-        b = a % b;
-        a = t;
-    }
-    a
-}
-
-fn lcm(a: usize, b: usize) -> usize {
-    a * b / gcd(a, b)
-}
-
 fn main() -> common::Result<()> {
-    solve(
-        |input: &Map| input.find_z("AAA"),
+    try_solve(
+        |input: &Map| {
+            let start = input.nodes.id("AAA").context("expected node AAA")?;
+            earliest_together(&[input.trajectory(start)])
+        },
         |input| {
-            let start_nodes = input
+            let ghosts = input
                 .nodes
-                .keys()
-                .filter(|name| name.ends_with('A'))
-                .map(|name| name.as_str())
+                .nodes()
+                .filter(|&node| input.nodes.label(node).ends_with('A'))
+                .map(|start| input.trajectory(start))
                 .collect::<Vec<_>>();
-            start_nodes
-                .iter()
-                .map(|start| input.find_z(start))
-                .fold(1, lcm)
+            earliest_together(&ghosts)
         },
     )
 }
@@ -1,48 +1,18 @@
 use std::str::FromStr;
 
-use common::{solve, Lines};
+use common::{poly, solve, Lines};
 
 struct History {
     values: Vec<isize>,
 }
 
 impl History {
-    fn differences(&self) -> Vec<Vec<isize>> {
-        let mut differences = Vec::new();
-        loop {
-            let mut difference = Vec::new();
-            let base = differences.last().unwrap_or(&self.values);
-
-            let mut all_zero = true;
-            for window in base.windows(2) {
-                let d = window[1] - window[0];
-                all_zero = all_zero && d == 0;
-                difference.push(d);
-            }
-
-            if all_zero {
-                break;
-            }
-            differences.push(difference);
-        }
-        differences
-    }
-
     fn predict(&self) -> isize {
-        self.differences()
-            .iter()
-            .map(|d| d.last().unwrap())
-            .sum::<isize>()
-            + self.values.last().unwrap()
+        poly::extrapolate(&self.values)
     }
 
     fn predict_rev(&self) -> isize {
-        self.values.first().unwrap()
-            - self
-                .differences()
-                .iter()
-                .rev()
-                .fold(0, |prev, d| d.first().unwrap() - prev)
+        poly::extrapolate_back(&self.values)
     }
 }
 